@@ -11,7 +11,7 @@ use sai_defects::AppPlugin;
 // Example: `RUST_LOG=off cargo run --release --bin debug_dump | dot -Tsvg | feh -`
 fn main() {
     let mut app = App::new();
-    app.add_plugins(AppPlugin);
+    app.add_plugins(AppPlugin::default());
 
     let mut labels = app
         .world
@@ -1,9 +1,42 @@
 // Disable console on windows for release builds
 #![cfg_attr(not(feature = "dev"), windows_subsystem = "windows")]
 
+use std::env;
+use std::process::ExitCode;
+
 use bevy::prelude::*;
+use sai_defects::check_config;
 use sai_defects::AppPlugin;
 
-fn main() {
-    App::new().add_plugins(AppPlugin).run();
+// Usage: `run [--record <file>] [--replay <file>]` or `run --check-config <file>`
+fn main() -> ExitCode {
+    let mut plugin = AppPlugin::default();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--record" => plugin.record = args.next().map(Into::into),
+            "--replay" => plugin.replay = args.next().map(Into::into),
+            "--check-config" => {
+                let Some(path) = args.next() else {
+                    eprintln!("Missing file for --check-config");
+                    return ExitCode::FAILURE;
+                };
+                return match check_config(&path) {
+                    Ok(()) => {
+                        println!("{path}: OK");
+                        ExitCode::SUCCESS
+                    },
+                    Err(e) => {
+                        eprintln!("{e}");
+                        ExitCode::FAILURE
+                    },
+                };
+            },
+            _ => eprintln!("Ignoring unknown argument: {arg}"),
+        }
+    }
+
+    App::new().add_plugins(plugin).run();
+    ExitCode::SUCCESS
 }
@@ -1,50 +1,68 @@
 //! Foundational features and cross-cutting concerns
 
-mod audio;
+pub mod audio;
 pub mod camera;
 pub mod config;
 #[cfg(feature = "dev")]
 mod debug;
-mod music;
+pub mod keybindings;
+pub mod music;
 mod physics;
+pub mod rng;
+pub mod storage;
 pub mod theme;
 pub mod window;
 
+use std::time::Duration;
+
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::ecs::schedule::ScheduleLabel;
 use bevy::prelude::*;
+use bevy::render::settings::WgpuSettings;
+use bevy::render::RenderPlugin;
 use bevy::transform::TransformSystem;
 use bevy::ui::UiSystem;
 use bevy::window::WindowPlugin as BevyWindowPlugin;
+use bevy::winit::WinitPlugin;
 use bevy_rapier2d::plugin::PhysicsSet;
 
-pub struct CommonPlugin;
+pub struct CommonPlugin {
+    pub headless: bool,
+}
 
 impl Plugin for CommonPlugin {
     fn build(&self, app: &mut App) {
-        // Game logic system ordering
-        app.configure_sets(
-            Update,
-            (
-                UpdateSet::HandleActions,
-                UpdateSet::HandleActionsFlush,
-                UpdateSet::Start,
-                UpdateSet::Update,
-                UpdateSet::RecordIntents,
-                UpdateSet::ApplyIntents,
-                UpdateSet::HandleEvents,
-                UpdateSet::QueueDespawn,
-                UpdateSet::ApplyDeferred,
-                UpdateSet::UpdateUi,
-                UpdateSet::End,
+        // Game logic system ordering (the same ordering applies to both schedules)
+        for schedule in [Update.intern(), FixedUpdate.intern()] {
+            app.configure_sets(
+                schedule,
+                (
+                    UpdateSet::HandleActions,
+                    UpdateSet::HandleActionsFlush,
+                    UpdateSet::Start,
+                    UpdateSet::Update,
+                    UpdateSet::RecordIntents,
+                    UpdateSet::BlockIntents,
+                    UpdateSet::ApplyIntents,
+                    UpdateSet::HandleEvents,
+                    UpdateSet::QueueDespawn,
+                    UpdateSet::ApplyDeferred,
+                    UpdateSet::UpdateUi,
+                    UpdateSet::End,
+                )
+                    .chain(),
             )
-                .chain(),
-        )
-        .add_systems(
-            Update,
-            (
-                apply_deferred.in_set(UpdateSet::HandleActionsFlush),
-                apply_deferred.in_set(UpdateSet::ApplyDeferred),
-            ),
-        );
+            .add_systems(
+                schedule,
+                (
+                    apply_deferred.in_set(UpdateSet::HandleActionsFlush),
+                    apply_deferred.in_set(UpdateSet::ApplyDeferred),
+                ),
+            );
+        }
+
+        // Step physics after game logic in each fixed timestep
+        app.configure_sets(FixedUpdate, UpdateSet::End.before(PhysicsSet::SyncBackend));
 
         // Post-processing system ordering
         app.configure_sets(
@@ -66,36 +84,67 @@ impl Plugin for CommonPlugin {
         app.insert_resource(bevy::asset::AssetMetaCheck::Never);
 
         // Bevy plugins
-        app.add_plugins(
-            DefaultPlugins
-                .build()
-                .disable::<BevyWindowPlugin>()
-                .add_after::<BevyWindowPlugin, _>(window::WindowPlugin)
-                .set(ImagePlugin::default_nearest()),
-        );
+        let bevy_plugins = DefaultPlugins
+            .build()
+            .disable::<BevyWindowPlugin>()
+            .add_after::<BevyWindowPlugin, _>(window::WindowPlugin {
+                headless: self.headless,
+            })
+            .set(ImagePlugin::default_nearest());
+        if self.headless {
+            // Keep asset loading and the ECS plugins, but drop winit and the GPU
+            app.add_plugins((
+                bevy_plugins.disable::<WinitPlugin>().set(RenderPlugin {
+                    render_creation: WgpuSettings {
+                        backends: None,
+                        ..default()
+                    }
+                    .into(),
+                    ..default()
+                }),
+                ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1.0 / 60.0)),
+            ));
+        } else {
+            app.add_plugins(bevy_plugins);
+        }
 
         // Other plugins
         app.add_plugins((
-            audio::AudioPlugin,
-            camera::CameraPlugin,
+            audio::AudioPlugin {
+                headless: self.headless,
+            },
+            camera::CameraPlugin {
+                headless: self.headless,
+            },
+            // Storage must come before the plugins that load from it
+            storage::StoragePlugin {
+                headless: self.headless,
+            },
             config::ConfigPlugin,
             music::MusicPlugin,
             physics::PhysicsPlugin,
+            rng::RngPlugin,
+            keybindings::KeybindingsPlugin,
             theme::ThemePlugin,
         ));
 
         // Debugging tools for dev builds
         #[cfg(feature = "dev")]
-        app.add_plugins(debug::DebugPlugin {
-            log_diagnostics: false,
-            log_ambiguity_detection: false,
-            //editor: false,
-            ..default()
-        });
+        if !self.headless {
+            app.add_plugins(debug::DebugPlugin {
+                log_diagnostics: false,
+                log_ambiguity_detection: false,
+                //editor: false,
+                ..default()
+            });
+        }
     }
 }
 
-/// (Update) Game logic system ordering
+/// (Update, FixedUpdate) Game logic system ordering
+///
+/// Gameplay systems run in FixedUpdate so that outcomes don't depend on frame rate.
+/// Handling just-pressed actions and end-of-frame presentation stays in Update.
 #[derive(SystemSet, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum UpdateSet {
     /// Handle actions pressed this frame
@@ -108,6 +157,8 @@ pub enum UpdateSet {
     Update,
     /// Record player and AI intents
     RecordIntents,
+    /// Override intents that can't be acted on (e.g. while stunned)
+    BlockIntents,
     /// Apply player and AI intents
     ApplyIntents,
     /// Handle events emitted this frame
@@ -1,10 +1,162 @@
 use bevy::prelude::*;
+use bevy::window::WindowFocused;
+use bevy_kira_audio::prelude::*;
 use bevy_kira_audio::AudioPlugin as KiraAudioPlugin;
+use serde::de::Error as _;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
 
-pub struct AudioPlugin;
+use crate::common::config::ConfigMerger;
+use crate::common::window::WindowRoot;
+use crate::common::UpdateSet;
+
+pub struct AudioPlugin {
+    pub headless: bool,
+}
 
 impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(KiraAudioPlugin);
+        if self.headless {
+            // Stub audio: sounds can still be requested, but nothing will be played
+            app.init_asset::<AudioSource>()
+                .init_asset::<AudioInstance>()
+                .init_resource::<AudioChannel<MusicChannel>>()
+                .init_resource::<AudioChannel<SfxChannel>>()
+                .init_resource::<AudioChannel<UiChannel>>();
+        } else {
+            app.add_plugins(KiraAudioPlugin)
+                .add_audio_channel::<MusicChannel>()
+                .add_audio_channel::<SfxChannel>()
+                .add_audio_channel::<UiChannel>();
+        }
+
+        app.register_type::<AudioConfig>()
+            .init_resource::<AudioConfig>()
+            .add_systems(
+                Update,
+                apply_volume
+                    .in_set(UpdateSet::End)
+                    .run_if(resource_changed::<AudioConfig>.or_else(on_event::<WindowFocused>())),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub struct MusicChannel;
+
+impl MusicChannel {
+    /// Mix level of the music at full channel volume
+    const LEVEL: f64 = 0.4;
+}
+
+#[derive(Resource)]
+pub struct SfxChannel;
+
+/// Mix levels of individual sounds, relative to the channel volume
+impl SfxChannel {
+    pub const FOOTSTEP: f64 = 0.3;
+    pub const HIT: f64 = 0.4;
+    pub const ALERT: f64 = 0.6;
+    pub const ITEM: f64 = 0.6;
+    pub const SWITCH: f64 = 0.8;
+}
+
+#[derive(Resource)]
+pub struct UiChannel;
+
+/// Volumes are multiplied by the master volume
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+pub struct AudioConfig {
+    pub master_volume: f64,
+    pub music_volume: f64,
+    pub sfx_volume: f64,
+    pub ui_volume: f64,
+    pub mute_when_unfocused: bool,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            ui_volume: 1.0,
+            mute_when_unfocused: true,
+        }
     }
 }
+
+impl AudioConfig {
+    pub fn apply(&self, world: &mut World) {
+        world.insert_resource(self.clone());
+    }
+
+    pub fn merge(&mut self, layer: &AudioConfigLayer, merger: &mut ConfigMerger) {
+        merger.merge(
+            "audio.master_volume",
+            &mut self.master_volume,
+            &layer.master_volume,
+        );
+        merger.merge(
+            "audio.music_volume",
+            &mut self.music_volume,
+            &layer.music_volume,
+        );
+        merger.merge("audio.sfx_volume", &mut self.sfx_volume, &layer.sfx_volume);
+        merger.merge("audio.ui_volume", &mut self.ui_volume, &layer.ui_volume);
+        merger.merge(
+            "audio.mute_when_unfocused",
+            &mut self.mute_when_unfocused,
+            &layer.mute_when_unfocused,
+        );
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AudioConfigLayer {
+    #[serde(deserialize_with = "volume", skip_serializing_if = "Option::is_none")]
+    pub master_volume: Option<f64>,
+    #[serde(deserialize_with = "volume", skip_serializing_if = "Option::is_none")]
+    pub music_volume: Option<f64>,
+    #[serde(deserialize_with = "volume", skip_serializing_if = "Option::is_none")]
+    pub sfx_volume: Option<f64>,
+    #[serde(deserialize_with = "volume", skip_serializing_if = "Option::is_none")]
+    pub ui_volume: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mute_when_unfocused: Option<bool>,
+}
+
+fn volume<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    let volume = Option::<f64>::deserialize(deserializer)?;
+    if let Some(volume) = volume.filter(|volume| !(0.0..=1.0).contains(volume)) {
+        return Err(D::Error::custom(format!(
+            "volume must be between 0.0 and 1.0, found {volume}"
+        )));
+    }
+
+    Ok(volume)
+}
+
+fn apply_volume(
+    config: Res<AudioConfig>,
+    window_root: Res<WindowRoot>,
+    window_query: Query<&Window>,
+    music: Res<AudioChannel<MusicChannel>>,
+    sfx: Res<AudioChannel<SfxChannel>>,
+    ui: Res<AudioChannel<UiChannel>>,
+) {
+    let focused = window_query
+        .get(window_root.primary)
+        .map_or(true, |window| window.focused);
+    let master = if config.mute_when_unfocused && !focused {
+        0.0
+    } else {
+        config.master_volume
+    };
+
+    music.set_volume(master * config.music_volume * MusicChannel::LEVEL);
+    sfx.set_volume(master * config.sfx_volume);
+    ui.set_volume(master * config.ui_volume);
+}
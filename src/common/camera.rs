@@ -5,12 +5,26 @@ use crate::common::PostTransformSet;
 use crate::common::UpdateSet;
 use crate::game::actor::player::PlayerControl;
 
-pub struct CameraPlugin;
+pub struct CameraPlugin {
+    pub headless: bool,
+}
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(Msaa::Off);
 
+        if self.headless {
+            // Keep a camera-like entity around for GameCamera to follow the player without rendering
+            let primary = app
+                .world
+                .spawn((
+                    Name::new("PrimaryCamera"),
+                    SpatialBundle::default(),
+                    GameCamera { rate: 5.0 },
+                ))
+                .id();
+            app.insert_resource(CameraRoot { primary });
+        }
         app.register_type::<CameraRoot>()
             .init_resource::<CameraRoot>();
 
@@ -1,57 +1,277 @@
-use bevy::ecs::event::ManualEventReader;
+use std::collections::BTreeMap;
+
+use bevy::asset::io::Reader;
+use bevy::asset::AssetLoader;
+use bevy::asset::AsyncReadExt;
+use bevy::asset::LoadContext;
+use bevy::asset::LoadState;
 use bevy::prelude::*;
-use bevy_common_assets::ron::RonAssetPlugin;
+use bevy::utils::BoxedFuture;
+use ron::error::SpannedError;
+use ron::extensions::Extensions;
+use ron::ser::PrettyConfig;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::common::audio::AudioConfig;
+use crate::common::audio::AudioConfigLayer;
+use crate::common::keybindings::KeybindingsConfig;
+use crate::common::keybindings::KeybindingsConfigLayer;
+use crate::common::storage::ConfigStorage;
 use crate::common::theme::ThemeConfig;
+use crate::common::theme::ThemeConfigLayer;
 use crate::common::window::WindowConfig;
+use crate::common::window::WindowConfigLayer;
 
 pub struct ConfigPlugin;
 
 impl Plugin for ConfigPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Config>()
-            .add_plugins(RonAssetPlugin::<Config>::new(&["config.ron"]))
+            .init_resource::<Config>()
+            .init_resource::<ConfigSources>()
+            .init_resource::<UserConfig>()
+            .init_asset::<ConfigLayer>()
+            .init_asset_loader::<ConfigLayerLoader>()
             .add_systems(Startup, load_config)
             .add_systems(
                 PreUpdate,
-                apply_config.run_if(on_event::<AssetEvent<Config>>()),
+                apply_config.run_if(
+                    on_event::<AssetEvent<ConfigLayer>>()
+                        .or_else(resource_changed::<UserConfig>)
+                        .or_else(default_layer_failed),
+                ),
             );
     }
 }
 
 #[derive(Resource)]
-pub struct ConfigHandle(pub Handle<Config>);
+pub struct ConfigHandle {
+    pub default: Handle<ConfigLayer>,
+    #[cfg(feature = "dev")]
+    pub dev: Handle<ConfigLayer>,
+}
 
-// TODO: DevConfig
-#[derive(Asset, Reflect, Serialize, Deserialize)]
+/// The merged config: built-in defaults, then `default.config.ron`, then `dev.config.ron` (dev
+/// builds only), then the user layer
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
 #[reflect(from_reflect = false)]
 pub struct Config {
     pub window: WindowConfig,
     pub theme: ThemeConfig,
-    // TODO: Volume
-    // TODO: Mute when out of focus
-    // TODO: Keybindings
+    pub audio: AudioConfig,
+    pub keybindings: KeybindingsConfig,
+}
+
+impl Config {
+    fn merge(&mut self, layer: &ConfigLayer, merger: &mut ConfigMerger) {
+        self.window.merge(&layer.window, merger);
+        self.theme.merge(&layer.theme, merger);
+        self.audio.merge(&layer.audio, merger);
+        self.keybindings.merge(&layer.keybindings, merger);
+    }
+}
+
+/// A config file, where every field is optional
+#[derive(Asset, TypePath, Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct ConfigLayer {
+    pub window: WindowConfigLayer,
+    pub theme: ThemeConfigLayer,
+    pub audio: AudioConfigLayer,
+    pub keybindings: KeybindingsConfigLayer,
+}
+
+impl ConfigLayer {
+    /// Parse and validate a config file (errors include the line and column)
+    pub fn parse(bytes: &[u8]) -> Result<Self, SpannedError> {
+        ron::de::from_bytes(bytes)
+    }
+}
+
+#[derive(Default)]
+struct ConfigLayerLoader;
+
+impl AssetLoader for ConfigLayerLoader {
+    type Asset = ConfigLayer;
+    type Settings = ();
+    type Error = ConfigError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = vec![];
+            reader
+                .read_to_end(&mut bytes)
+                .await
+                .map_err(ConfigError::Io)?;
+            ConfigLayer::parse(&bytes).map_err(|error| ConfigError::Parse {
+                path: load_context.path().display().to_string(),
+                error,
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["config.ron"]
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse { path: String, error: SpannedError },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{error}"),
+            // SpannedError is formatted as `line:column: message`
+            Self::Parse { path, error } => write!(f, "{path}:{error}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// The user layer, saved to the platform config directory
+#[derive(Resource)]
+pub struct UserConfig(pub ConfigLayer);
+
+impl FromWorld for UserConfig {
+    fn from_world(world: &mut World) -> Self {
+        let layer = world
+            .resource::<ConfigStorage>()
+            .0
+            .read(Self::KEY)
+            .and_then(|text| {
+                ConfigLayer::parse(text.as_bytes())
+                    .map_err(|e| error!("Failed to parse {}:{e}", Self::KEY))
+                    .ok()
+            })
+            .unwrap_or_default();
+
+        Self(layer)
+    }
+}
+
+impl UserConfig {
+    const KEY: &'static str = "user.config.ron";
+
+    pub fn save(&self, storage: &mut ConfigStorage) {
+        let result = ron::ser::to_string_pretty(
+            &self.0,
+            PrettyConfig::default().extensions(Extensions::IMPLICIT_SOME),
+        )
+        .map_err(|e| e.to_string())
+        .and_then(|text| storage.0.write(Self::KEY, &text));
+        if let Err(e) = result {
+            error!("Failed to save {}: {e}", Self::KEY);
+        }
+    }
+}
+
+/// The layer that each config value came from (values not listed are built-in)
+#[derive(Resource, Default)]
+pub struct ConfigSources(pub BTreeMap<String, &'static str>);
+
+pub struct ConfigMerger<'a> {
+    layer: &'static str,
+    sources: &'a mut ConfigSources,
+}
+
+impl ConfigMerger<'_> {
+    /// Override the target if the layer has a value for it
+    pub fn merge<T: Clone>(&mut self, path: impl Into<String>, target: &mut T, value: &Option<T>) {
+        if let Some(value) = value {
+            self.set(path, target, value);
+        }
+    }
+
+    pub fn set<T: Clone>(&mut self, path: impl Into<String>, target: &mut T, value: &T) {
+        target.clone_from(value);
+        self.sources.0.insert(path.into(), self.layer);
+    }
 }
 
 fn load_config(mut commands: Commands, ass: Res<AssetServer>) {
-    commands.insert_resource(ConfigHandle(ass.load("default.config.ron")));
+    commands.insert_resource(ConfigHandle {
+        default: ass.load("default.config.ron"),
+        #[cfg(feature = "dev")]
+        dev: ass.load("dev.config.ron"),
+    });
+}
+
+/// Whether the default layer failed to load (true only once)
+fn default_layer_failed(
+    ass: Res<AssetServer>,
+    config_handle: Option<Res<ConfigHandle>>,
+    mut reported: Local<bool>,
+) -> bool {
+    let failed = config_handle.is_some_and(|config_handle| {
+        matches!(
+            ass.get_load_state(&config_handle.default),
+            Some(LoadState::Failed),
+        )
+    });
+    let first_time = failed && !*reported;
+    *reported |= failed;
+
+    first_time
 }
 
-fn apply_config(world: &mut World, mut reader: Local<ManualEventReader<AssetEvent<Config>>>) {
-    if !reader
-        .read(world.resource::<Events<AssetEvent<_>>>())
-        .any(|event| event.is_loaded_with_dependencies(&world.resource::<ConfigHandle>().0))
-    {
+fn apply_config(world: &mut World) {
+    let config_handle = world.resource::<ConfigHandle>();
+    let layers = world.resource::<Assets<ConfigLayer>>();
+    let mut named_layers = vec![];
+    if let Some(default_layer) = layers.get(&config_handle.default) {
+        named_layers.push(("default", default_layer));
+    } else if matches!(
+        world
+            .resource::<AssetServer>()
+            .get_load_state(&config_handle.default),
+        Some(LoadState::Failed),
+    ) {
+        warn!("Falling back to the built-in config");
+    } else {
         return;
     }
+    #[cfg(feature = "dev")]
+    if let Some(dev_layer) = layers.get(&config_handle.dev) {
+        named_layers.push(("dev", dev_layer));
+    }
+    named_layers.push(("user", &world.resource::<UserConfig>().0));
+
+    // Merge layers
+    let mut config = Config::default();
+    let mut sources = ConfigSources::default();
+    for (layer, values) in named_layers {
+        config.merge(
+            values,
+            &mut ConfigMerger {
+                layer,
+                sources: &mut sources,
+            },
+        );
+    }
 
     info!("Applying config");
-    world.resource_scope(|world, config: Mut<Assets<Config>>| {
-        let config = config.get(&world.resource::<ConfigHandle>().0).unwrap();
+    for (path, layer) in &sources.0 {
+        if *layer != "default" {
+            info!("Config value {path} is from the {layer} layer");
+        }
+    }
 
-        config.window.apply(world);
-        config.theme.apply(world);
-    });
+    config.window.apply(world);
+    config.theme.apply(world);
+    config.audio.apply(world);
+    config.keybindings.apply(world);
+    world.insert_resource(config);
+    world.insert_resource(sources);
 }
@@ -0,0 +1,326 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use leafwing_input_manager::plugin::InputManagerSystem;
+use leafwing_input_manager::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::common::config::ConfigMerger;
+use crate::common::config::UserConfig;
+use crate::common::storage::ConfigStorage;
+use crate::game::actor::player::PlayerAction;
+use crate::game::cutscene::CutsceneAction;
+use crate::sequence::game::GameAction;
+
+pub struct KeybindingsPlugin;
+
+impl Plugin for KeybindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Keybindings>()
+            .init_resource::<InputMap<GameAction>>()
+            .add_systems(
+                PreUpdate,
+                apply_keybindings.before(InputManagerSystem::Update),
+            );
+    }
+}
+
+/// A single input, or a group of inputs that act as one
+#[derive(Reflect, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+    KeyPad {
+        up: KeyCode,
+        down: KeyCode,
+        left: KeyCode,
+        right: KeyCode,
+    },
+    LeftStick,
+    RightStick,
+}
+
+impl Binding {
+    fn user_input(&self) -> UserInput {
+        match *self {
+            Self::Key(key) => key.into(),
+            Self::Mouse(button) => button.into(),
+            Self::Gamepad(button) => button.into(),
+            Self::KeyPad {
+                up,
+                down,
+                left,
+                right,
+            } => VirtualDPad {
+                up: InputKind::PhysicalKey(up),
+                down: InputKind::PhysicalKey(down),
+                left: InputKind::PhysicalKey(left),
+                right: InputKind::PhysicalKey(right),
+            }
+            .into(),
+            Self::LeftStick => DualAxis::left_stick().into(),
+            Self::RightStick => DualAxis::right_stick().into(),
+        }
+    }
+
+    fn device(&self) -> Device {
+        match self {
+            Self::Key(_) | Self::KeyPad { .. } => Device::Keyboard,
+            Self::Mouse(_) => Device::Mouse,
+            Self::Gamepad(_) | Self::LeftStick | Self::RightStick => Device::Gamepad,
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum Device {
+    Keyboard,
+    Mouse,
+    Gamepad,
+}
+
+impl std::fmt::Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Key(key) => write!(f, "{key:?}"),
+            Self::Mouse(button) => write!(f, "Mouse {button:?}"),
+            Self::Gamepad(button) => write!(f, "{button:?}"),
+            Self::KeyPad {
+                up,
+                down,
+                left,
+                right,
+            } => write!(f, "{up:?}/{left:?}/{down:?}/{right:?}"),
+            Self::LeftStick => write!(f, "Left Stick"),
+            Self::RightStick => write!(f, "Right Stick"),
+        }
+    }
+}
+
+/// Any action that can be rebound
+#[derive(Reflect, Clone, PartialEq, Debug)]
+pub enum BoundAction {
+    Player(PlayerAction),
+    Game(GameAction),
+    Cutscene(CutsceneAction),
+}
+
+#[derive(Reflect, Serialize, Deserialize, Clone)]
+pub struct KeybindingsConfig {
+    pub player: HashMap<PlayerAction, Vec<Binding>>,
+    pub game: HashMap<GameAction, Vec<Binding>>,
+    pub cutscene: HashMap<CutsceneAction, Vec<Binding>>,
+}
+
+impl Default for KeybindingsConfig {
+    fn default() -> Self {
+        let wasd = Binding::KeyPad {
+            up: KeyCode::KeyW,
+            down: KeyCode::KeyS,
+            left: KeyCode::KeyA,
+            right: KeyCode::KeyD,
+        };
+        let arrow_keys = Binding::KeyPad {
+            up: KeyCode::ArrowUp,
+            down: KeyCode::ArrowDown,
+            left: KeyCode::ArrowLeft,
+            right: KeyCode::ArrowRight,
+        };
+
+        Self {
+            player: HashMap::from_iter([
+                (
+                    PlayerAction::Move,
+                    vec![wasd, arrow_keys, Binding::LeftStick],
+                ),
+                (PlayerAction::Aim, vec![Binding::RightStick]),
+                (
+                    PlayerAction::Attack,
+                    vec![
+                        Binding::Mouse(MouseButton::Left),
+                        Binding::Gamepad(GamepadButtonType::RightTrigger),
+                    ],
+                ),
+                (
+                    PlayerAction::Throw,
+                    vec![
+                        Binding::Mouse(MouseButton::Right),
+                        Binding::Gamepad(GamepadButtonType::LeftTrigger),
+                    ],
+                ),
+                (
+                    PlayerAction::Dodge,
+                    vec![
+                        Binding::Key(KeyCode::Space),
+                        Binding::Key(KeyCode::ShiftLeft),
+                        Binding::Gamepad(GamepadButtonType::South),
+                    ],
+                ),
+                (
+                    PlayerAction::Interact,
+                    vec![
+                        Binding::Key(KeyCode::KeyE),
+                        Binding::Gamepad(GamepadButtonType::West),
+                    ],
+                ),
+                (
+                    PlayerAction::Drink,
+                    vec![
+                        Binding::Key(KeyCode::KeyQ),
+                        Binding::Gamepad(GamepadButtonType::North),
+                    ],
+                ),
+            ]),
+            game: HashMap::from_iter([
+                (GameAction::Restart, vec![Binding::Key(KeyCode::KeyR)]),
+                (GameAction::HighScores, vec![Binding::Key(KeyCode::KeyH)]),
+                (
+                    GameAction::Pause,
+                    vec![
+                        Binding::Key(KeyCode::Escape),
+                        Binding::Gamepad(GamepadButtonType::Start),
+                    ],
+                ),
+            ]),
+            cutscene: HashMap::from_iter([(
+                CutsceneAction::Advance,
+                vec![
+                    Binding::Key(KeyCode::Space),
+                    Binding::Key(KeyCode::Enter),
+                    Binding::Mouse(MouseButton::Left),
+                ],
+            )]),
+        }
+    }
+}
+
+impl KeybindingsConfig {
+    pub fn apply(&self, world: &mut World) {
+        world.insert_resource(Keybindings(self.clone()));
+    }
+
+    /// Each action in the layer replaces all of that action's bindings
+    pub fn merge(&mut self, layer: &KeybindingsConfigLayer, merger: &mut ConfigMerger) {
+        for (action, bindings) in &layer.player {
+            let path = format!("keybindings.player.{action:?}");
+            merger.set(
+                path,
+                self.player.entry(action.clone()).or_default(),
+                bindings,
+            );
+        }
+        for (action, bindings) in &layer.game {
+            let path = format!("keybindings.game.{action:?}");
+            merger.set(path, self.game.entry(action.clone()).or_default(), bindings);
+        }
+        for (action, bindings) in &layer.cutscene {
+            let path = format!("keybindings.cutscene.{action:?}");
+            merger.set(
+                path,
+                self.cutscene.entry(action.clone()).or_default(),
+                bindings,
+            );
+        }
+    }
+
+    fn get(&self, action: &BoundAction) -> &[Binding] {
+        match action {
+            BoundAction::Player(action) => self.player.get(action),
+            BoundAction::Game(action) => self.game.get(action),
+            BoundAction::Cutscene(action) => self.cutscene.get(action),
+        }
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct KeybindingsConfigLayer {
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub player: HashMap<PlayerAction, Vec<Binding>>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub game: HashMap<GameAction, Vec<Binding>>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub cutscene: HashMap<CutsceneAction, Vec<Binding>>,
+}
+
+impl KeybindingsConfigLayer {
+    fn insert(&mut self, action: BoundAction, bindings: Vec<Binding>) {
+        match action {
+            BoundAction::Player(action) => self.player.insert(action, bindings),
+            BoundAction::Game(action) => self.game.insert(action, bindings),
+            BoundAction::Cutscene(action) => self.cutscene.insert(action, bindings),
+        };
+    }
+}
+
+/// The merged keybindings from all config layers
+#[derive(Resource, Default)]
+pub struct Keybindings(pub KeybindingsConfig);
+
+impl Keybindings {
+    pub fn get(&self, action: &BoundAction) -> &[Binding] {
+        self.0.get(action)
+    }
+
+    /// Replace the action's first binding on the same device (keyboard, mouse or gamepad),
+    /// keeping its other bindings, and save the result to the user layer
+    pub fn rebind(
+        &self,
+        action: BoundAction,
+        binding: Binding,
+        user_config: &mut UserConfig,
+        storage: &mut ConfigStorage,
+    ) {
+        let mut bindings = self.get(&action).to_vec();
+        if bindings.contains(&binding) {
+            return;
+        }
+        match bindings
+            .iter()
+            .position(|old| old.device() == binding.device())
+        {
+            Some(i) => bindings[i] = binding,
+            None => bindings.push(binding),
+        }
+
+        user_config.0.keybindings.insert(action, bindings);
+        user_config.save(storage);
+    }
+
+    fn input_map<A: Actionlike>(bindings: &HashMap<A, Vec<Binding>>) -> InputMap<A> {
+        let mut input_map = InputMap::default();
+        for (action, bindings) in bindings {
+            for binding in bindings {
+                input_map.insert(action.clone(), binding.user_input());
+            }
+        }
+
+        input_map
+    }
+}
+
+fn apply_keybindings(
+    keybindings: Res<Keybindings>,
+    mut game_input_map: ResMut<InputMap<GameAction>>,
+    mut player_query: Query<&mut InputMap<PlayerAction>>,
+    mut cutscene_query: Query<&mut InputMap<CutsceneAction>>,
+) {
+    if keybindings.is_changed() {
+        *game_input_map = Keybindings::input_map(&keybindings.0.game);
+    }
+
+    for mut input_map in &mut player_query {
+        if keybindings.is_changed() || input_map.is_added() {
+            *input_map = Keybindings::input_map(&keybindings.0.player);
+        }
+    }
+
+    for mut input_map in &mut cutscene_query {
+        if keybindings.is_changed() || input_map.is_added() {
+            *input_map = Keybindings::input_map(&keybindings.0.cutscene);
+        }
+    }
+}
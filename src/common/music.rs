@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 use bevy_kira_audio::prelude::*;
 
+use crate::common::audio::MusicChannel;
 use crate::game::actor::player::Playthrough;
 use crate::game::level::victory::Victory;
 
@@ -40,7 +41,7 @@ fn update_music(
     music_assets: Res<MusicAssets>,
     playthrough: Res<Playthrough>,
     victory: Res<Victory>,
-    audio: Res<Audio>,
+    audio: Res<AudioChannel<MusicChannel>>,
 ) {
     let next = if victory.0 {
         Some(music_assets.victory.clone())
@@ -66,5 +67,5 @@ fn update_music(
     music.track = music
         .current
         .clone()
-        .map(|source| audio.play(source).with_volume(0.4).looped().handle());
+        .map(|source| audio.play(source).looped().handle());
 }
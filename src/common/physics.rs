@@ -1,14 +1,23 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
+/// The rate at which gameplay logic and physics are stepped
+pub const FIXED_TIMESTEP_HZ: f64 = 60.0;
+
 pub struct PhysicsPlugin;
 
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(RapierConfiguration {
-            gravity: Vec2::ZERO,
-            ..default()
-        })
-        .add_plugins(RapierPhysicsPlugin::<NoUserData>::default());
+        app.insert_resource(Time::<Fixed>::from_hz(FIXED_TIMESTEP_HZ))
+            .insert_resource(RapierConfiguration {
+                gravity: Vec2::ZERO,
+                // Step in lockstep with FixedUpdate
+                timestep_mode: TimestepMode::Fixed {
+                    dt: (1.0 / FIXED_TIMESTEP_HZ) as f32,
+                    substeps: 1,
+                },
+                ..default()
+            })
+            .add_plugins(RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule());
     }
 }
@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+pub struct StoragePlugin {
+    pub headless: bool,
+}
+
+impl Plugin for StoragePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SaveStorage(self.backend(dirs::data_dir)))
+            .insert_resource(ConfigStorage(self.backend(dirs::config_dir)));
+    }
+}
+
+impl StoragePlugin {
+    /// Use the given platform directory on native builds
+    #[allow(unused_variables)]
+    fn backend(&self, dir: fn() -> Option<std::path::PathBuf>) -> Box<dyn StorageBackend> {
+        if self.headless {
+            return Box::<MemoryStorage>::default();
+        }
+
+        #[cfg(feature = "web")]
+        let backend = Box::new(LocalStorage);
+        #[cfg(not(feature = "web"))]
+        let backend: Box<dyn StorageBackend> = match FileStorage::new(dir) {
+            Some(storage) => Box::new(storage),
+            None => {
+                warn!("No platform directory to save to, so nothing will persist after exit");
+                Box::<MemoryStorage>::default()
+            },
+        };
+        backend
+    }
+}
+
+/// Persistent key-value storage for save data
+pub trait StorageBackend: Send + Sync + 'static {
+    fn read(&self, key: &str) -> Option<String>;
+    fn write(&mut self, key: &str, value: &str) -> Result<(), String>;
+}
+
+/// Save data, like the run history
+#[derive(Resource)]
+pub struct SaveStorage(pub Box<dyn StorageBackend>);
+
+/// User settings, like the user config layer
+#[derive(Resource)]
+pub struct ConfigStorage(pub Box<dyn StorageBackend>);
+
+/// Stores each key as a file in a platform directory
+#[cfg(not(feature = "web"))]
+pub struct FileStorage {
+    pub dir: std::path::PathBuf,
+}
+
+#[cfg(not(feature = "web"))]
+impl FileStorage {
+    /// Returns `None` if the platform has no such directory
+    pub fn new(dir: fn() -> Option<std::path::PathBuf>) -> Option<Self> {
+        Some(Self {
+            dir: dir()?.join(env!("CARGO_PKG_NAME")),
+        })
+    }
+}
+
+#[cfg(not(feature = "web"))]
+impl StorageBackend for FileStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.dir.join(key)).ok()
+    }
+
+    fn write(&mut self, key: &str, value: &str) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        std::fs::write(self.dir.join(key), value).map_err(|e| e.to_string())
+    }
+}
+
+/// Stores each key in the browser's localStorage
+#[cfg(feature = "web")]
+pub struct LocalStorage;
+
+#[cfg(feature = "web")]
+impl LocalStorage {
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+}
+
+#[cfg(feature = "web")]
+impl StorageBackend for LocalStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        Self::storage()?.get_item(key).ok()?
+    }
+
+    fn write(&mut self, key: &str, value: &str) -> Result<(), String> {
+        Self::storage()
+            .ok_or("localStorage is unavailable")?
+            .set_item(key, value)
+            .map_err(|e| format!("{e:?}"))
+    }
+}
+
+/// Forgets everything on exit (for headless runs and tests)
+#[derive(Default)]
+pub struct MemoryStorage(pub HashMap<String, String>);
+
+impl StorageBackend for MemoryStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        self.0.get(key).cloned()
+    }
+
+    fn write(&mut self, key: &str, value: &str) -> Result<(), String> {
+        self.0.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+}
@@ -1,12 +1,15 @@
 use std::ops::Index;
 
 use bevy::prelude::*;
+use serde::de::Error as _;
 use serde::Deserialize;
+use serde::Deserializer;
 use serde::Serialize;
+use serde::Serializer;
 use strum::EnumCount;
 
 use crate::common::config::Config;
-use crate::common::config::ConfigHandle;
+use crate::common::config::ConfigMerger;
 use crate::common::UpdateSet;
 
 pub struct ThemePlugin;
@@ -27,7 +30,7 @@ impl Plugin for ThemePlugin {
     }
 }
 
-#[derive(Reflect, Serialize, Deserialize)]
+#[derive(Reflect, Serialize, Deserialize, Clone, Default)]
 pub struct ThemeConfig {
     pub colors: ThemeColorList,
     // TODO: pub fonts: ThemeFontList,
@@ -37,11 +40,68 @@ impl ThemeConfig {
     pub fn apply(&self, world: &mut World) {
         world.resource_mut::<ClearColor>().0 = self.colors[ThemeColor::Body];
     }
+
+    pub fn merge(&mut self, layer: &ThemeConfigLayer, merger: &mut ConfigMerger) {
+        merger.merge("theme.colors", &mut self.colors, &layer.colors);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ThemeConfigLayer {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colors: Option<ThemeColorList>,
 }
 
-#[derive(Reflect, Serialize, Deserialize)]
+/// One color per ThemeColor, in order
+#[derive(Reflect, Clone)]
 pub struct ThemeColorList([Color; ThemeColor::COUNT]);
 
+impl Serialize for ThemeColorList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.as_slice().serialize(serializer)
+    }
+}
+
+// Check the length here so that the error points at the list in the config file
+impl<'de> Deserialize<'de> for ThemeColorList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let colors = Vec::<Color>::deserialize(deserializer)?;
+        let len = colors.len();
+        colors.try_into().map(Self).map_err(|_| {
+            D::Error::custom(format!(
+                "expected {} theme colors (one per ThemeColor), found {len}",
+                ThemeColor::COUNT,
+            ))
+        })
+    }
+}
+
+impl Default for ThemeColorList {
+    fn default() -> Self {
+        Self([
+            // None
+            Color::rgba(0.000, 0.000, 0.000, 0.000),
+            // Body
+            Color::rgba(0.157, 0.157, 0.157, 1.000),
+            // BodyText
+            Color::rgba(0.925, 0.925, 0.925, 1.000),
+            // Primary
+            Color::rgba(0.000, 0.400, 0.700, 1.000),
+            // PrimaryHovered
+            Color::rgba(0.200, 0.500, 0.800, 1.000),
+            // PrimaryPressed
+            Color::rgba(0.000, 0.300, 0.500, 1.000),
+            // PrimaryDisabled
+            Color::rgba(0.000, 0.300, 0.500, 1.000),
+            // PrimaryText
+            Color::rgba(0.157, 0.157, 0.157, 1.000),
+            // Popup
+            Color::rgba(0.106, 0.118, 0.122, 0.850),
+        ])
+    }
+}
+
 impl Index<ThemeColor> for ThemeColorList {
     type Output = Color;
 
@@ -75,16 +135,10 @@ pub enum ThemeColor {
 pub struct ThemeSpriteColor(pub ThemeColor);
 
 fn apply_theme_sprite_color(
-    config_handle: Res<ConfigHandle>,
-    config: Res<Assets<Config>>,
+    config: Res<Config>,
     mut theme_query: Query<(&ThemeSpriteColor, &mut Sprite)>,
 ) {
-    let Some(palette) = &config
-        .get(&config_handle.0)
-        .map(|config| &config.theme.colors)
-    else {
-        return;
-    };
+    let palette = &config.theme.colors;
 
     for (color, mut sprite) in &mut theme_query {
         sprite.color = palette[color.0];
@@ -95,16 +149,10 @@ fn apply_theme_sprite_color(
 pub struct ThemeTextColors(pub Vec<ThemeColor>);
 
 fn apply_theme_text_colors(
-    config_handle: Res<ConfigHandle>,
-    config: Res<Assets<Config>>,
+    config: Res<Config>,
     mut theme_query: Query<(&ThemeTextColors, &mut Text)>,
 ) {
-    let Some(palette) = &config
-        .get(&config_handle.0)
-        .map(|config| &config.theme.colors)
-    else {
-        return;
-    };
+    let palette = &config.theme.colors;
 
     for (colors, mut text) in &mut theme_query {
         for (section, &color) in text.sections.iter_mut().zip(&colors.0) {
@@ -117,16 +165,10 @@ fn apply_theme_text_colors(
 pub struct ThemeBackgroundColor(pub ThemeColor);
 
 fn apply_theme_background_color(
-    config_handle: Res<ConfigHandle>,
-    config: Res<Assets<Config>>,
+    config: Res<Config>,
     mut theme_query: Query<(&ThemeBackgroundColor, &mut BackgroundColor)>,
 ) {
-    let Some(palette) = &config
-        .get(&config_handle.0)
-        .map(|config| &config.theme.colors)
-    else {
-        return;
-    };
+    let palette = &config.theme.colors;
 
     for (color, mut background) in &mut theme_query {
         background.0 = palette[color.0];
@@ -137,16 +179,10 @@ fn apply_theme_background_color(
 pub struct ThemeBorderColor(pub ThemeColor);
 
 fn apply_theme_border_color(
-    config_handle: Res<ConfigHandle>,
-    config: Res<Assets<Config>>,
+    config: Res<Config>,
     mut theme_query: Query<(&ThemeBorderColor, &mut BorderColor)>,
 ) {
-    let Some(palette) = &config
-        .get(&config_handle.0)
-        .map(|config| &config.theme.colors)
-    else {
-        return;
-    };
+    let palette = &config.theme.colors;
 
     for (color, mut border) in &mut theme_query {
         border.0 = palette[color.0];
@@ -7,25 +7,38 @@ use bevy::window::WindowPlugin as BevyWindowPlugin;
 use serde::Deserialize;
 use serde::Serialize;
 
-pub struct WindowPlugin;
+use crate::common::config::ConfigMerger;
+
+pub struct WindowPlugin {
+    pub headless: bool,
+}
 
 impl Plugin for WindowPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(BevyWindowPlugin {
-            primary_window: Some(Window {
-                canvas: Some("#bevy".to_string()),
-                prevent_default_event_handling: true,
+        if self.headless {
+            app.add_plugins(BevyWindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            });
+
+            // Stand-in for the primary window, so WindowRoot lookups fail gracefully
+            let primary = app.world.spawn(Name::new("HeadlessWindow")).id();
+            app.insert_resource(WindowRoot { primary });
+        } else {
+            app.add_plugins(BevyWindowPlugin {
+                primary_window: Some(Window {
+                    canvas: Some("#bevy".to_string()),
+                    prevent_default_event_handling: true,
+                    ..default()
+                }),
+                exit_condition: ExitCondition::OnPrimaryClosed,
                 ..default()
-            }),
-            exit_condition: ExitCondition::OnPrimaryClosed,
-            ..default()
-        });
+            });
+        }
 
         app.register_type::<WindowRoot>()
             .init_resource::<WindowRoot>();
-
-        #[cfg(not(feature = "web"))]
-        app.add_systems(Update, bevy::window::close_on_esc);
     }
 }
 
@@ -45,14 +58,38 @@ impl FromWorld for WindowRoot {
     }
 }
 
-#[derive(Reflect, Serialize, Deserialize)]
+#[derive(Reflect, Serialize, Deserialize, Clone)]
 pub struct WindowConfig {
     pub title: String,
     pub window_mode: WindowMode,
     pub present_mode: PresentMode,
 }
 
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            title: "Sai Defects".to_string(),
+            window_mode: WindowMode::Windowed,
+            present_mode: PresentMode::AutoVsync,
+        }
+    }
+}
+
 impl WindowConfig {
+    pub fn merge(&mut self, layer: &WindowConfigLayer, merger: &mut ConfigMerger) {
+        merger.merge("window.title", &mut self.title, &layer.title);
+        merger.merge(
+            "window.window_mode",
+            &mut self.window_mode,
+            &layer.window_mode,
+        );
+        merger.merge(
+            "window.present_mode",
+            &mut self.present_mode,
+            &layer.present_mode,
+        );
+    }
+
     pub fn apply(&self, world: &mut World) {
         let Some(mut window) = world.get_mut::<Window>(world.resource::<WindowRoot>().primary)
         else {
@@ -64,3 +101,14 @@ impl WindowConfig {
         window.present_mode = self.present_mode;
     }
 }
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct WindowConfigLayer {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_mode: Option<WindowMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub present_mode: Option<PresentMode>,
+}
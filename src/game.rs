@@ -6,7 +6,11 @@ pub mod actor;
 pub mod alarm;
 pub mod combat;
 pub mod cutscene;
+pub mod interact;
+pub mod inventory;
 pub mod level;
+pub mod noise;
+pub mod score;
 
 pub struct GamePlugin;
 
@@ -17,7 +21,11 @@ impl Plugin for GamePlugin {
             alarm::AlarmPlugin,
             combat::CombatPlugin,
             cutscene::CutscenePlugin,
+            interact::InteractPlugin,
+            inventory::InventoryPlugin,
             level::LevelPlugin,
+            noise::NoisePlugin,
+            score::ScorePlugin,
         ));
     }
 }
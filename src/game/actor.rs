@@ -1,8 +1,11 @@
+pub mod archetype;
 pub mod body;
+pub mod dodge;
 pub mod enemy;
 pub mod health;
 pub mod intent;
 pub mod player;
+pub mod search;
 
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
@@ -11,6 +14,8 @@ use bevy_rapier2d::prelude::*;
 
 use crate::game::actor::health::Health;
 use crate::game::actor::intent::ActorIntent;
+use crate::game::combat::status::StatusEffect;
+use crate::game::combat::status::StatusEffects;
 use crate::game::combat::Faction;
 use crate::game::combat::COLLISION_GROUP;
 use crate::util::animation::facing::Facing;
@@ -26,11 +31,14 @@ impl Plugin for ActorPlugin {
             .init_collection::<ActorAssets>();
 
         app.add_plugins((
+            archetype::ArchetypePlugin,
             body::BodyPlugin,
+            dodge::DodgePlugin,
             enemy::EnemyPlugin,
             health::HealthPlugin,
             intent::IntentPlugin,
             player::PlayerPlugin,
+            search::SearchPlugin,
         ));
     }
 }
@@ -51,6 +59,8 @@ pub struct ActorAssets {
     hurt: Handle<AudioSource>,
     #[asset(path = "sound/sfx/alert.wav")]
     alert: Handle<AudioSource>,
+    #[asset(path = "sound/sfx/pop_1.wav")]
+    loot: Handle<AudioSource>,
 }
 
 #[derive(Component, Reflect)]
@@ -60,6 +70,11 @@ pub struct Actor {
     pub brake_deceleration: f32,
     pub idle_threshold: f32,
     pub faction: Faction,
+    pub attack_damage: f32,
+    pub attack_knockback: f32,
+    pub hitbox_radius: f32,
+    /// Applied by melee hits
+    pub attack_status: Vec<StatusEffect>,
 }
 
 impl Actor {
@@ -70,6 +85,10 @@ impl Actor {
             brake_deceleration: 1800.0,
             idle_threshold: 10.0,
             faction: Faction::Player,
+            attack_damage: 8.0,
+            attack_knockback: 6.0,
+            hitbox_radius: 12.0,
+            attack_status: vec![],
         }
     }
 
@@ -80,6 +99,10 @@ impl Actor {
             brake_deceleration: 1800.0,
             idle_threshold: 10.0,
             faction: Faction::Enemy,
+            attack_damage: 8.0,
+            attack_knockback: 6.0,
+            hitbox_radius: 12.0,
+            attack_status: vec![],
         }
     }
 }
@@ -96,6 +119,7 @@ pub struct ActorBundle {
     pub actor_intent: ActorIntent,
     pub facing: Facing,
     pub health: Health,
+    pub status_effects: StatusEffects,
     pub velocity: Velocity,
     pub y_sort: YSort,
     pub rigid_body: RigidBody,
@@ -113,6 +137,7 @@ impl Default for ActorBundle {
             actor_intent: default(),
             facing: default(),
             health: Health::full(100.0),
+            status_effects: default(),
             y_sort: YSort,
             velocity: default(),
             rigid_body: default(),
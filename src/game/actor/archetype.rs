@@ -0,0 +1,189 @@
+use std::io;
+
+use bevy::asset::io::Reader;
+use bevy::asset::AssetLoader;
+use bevy::asset::AsyncReadExt;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use bevy::utils::HashMap;
+use bevy_asset_loader::prelude::*;
+use serde::Deserialize;
+
+use crate::game::actor::enemy::DifficultyCurve;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::actor::Actor;
+use crate::game::combat::projectile::RangedAttack;
+use crate::game::combat::status::StatusEffect;
+use crate::game::combat::DeathEffects;
+use crate::game::combat::HurtEffects;
+
+pub struct ArchetypePlugin;
+
+impl Plugin for ArchetypePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<EnemyArchetypes>()
+            .init_asset_loader::<EnemyArchetypesLoader>();
+
+        app.register_type::<ArchetypeAssets>()
+            .init_collection::<ArchetypeAssets>();
+
+        app.register_type::<Archetype>().add_systems(
+            PreUpdate,
+            reload_archetypes.run_if(on_event::<AssetEvent<EnemyArchetypes>>()),
+        );
+    }
+}
+
+#[derive(AssetCollection, Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct ArchetypeAssets {
+    #[asset(path = "enemy.archetypes.ron")]
+    pub enemies: Handle<EnemyArchetypes>,
+}
+
+/// Enemy archetypes by name
+#[derive(Asset, TypePath, Deserialize)]
+#[serde(transparent)]
+pub struct EnemyArchetypes(pub HashMap<String, EnemyArchetype>);
+
+impl EnemyArchetypes {
+    /// Falls back to the default archetype if the name is unknown
+    pub fn get(&self, name: &str) -> EnemyArchetype {
+        self.0.get(name).cloned().unwrap_or_else(|| {
+            warn!("Unknown enemy archetype: {name}");
+            default()
+        })
+    }
+}
+
+/// The default values are for a grunt
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct EnemyArchetype {
+    pub texture: String,
+    pub health: f32,
+    pub mass: f32,
+    pub acceleration: f32,
+    pub brake_deceleration: f32,
+    pub attack_damage: f32,
+    pub attack_knockback: f32,
+    pub hitbox_radius: f32,
+    /// Applied by melee hits
+    pub attack_status: Vec<StatusEffect>,
+    pub hurt_increase_alarm: f32,
+    pub death_increase_alarm: f32,
+    pub difficulty_curve: DifficultyCurve,
+    /// Ranged enemies shoot instead of attacking in melee
+    pub ranged: Option<RangedAttack>,
+}
+
+impl Default for EnemyArchetype {
+    fn default() -> Self {
+        Self {
+            texture: "image/actor/gnoll_red.png".to_string(),
+            health: 20.0,
+            mass: 1.0,
+            acceleration: 900.0,
+            brake_deceleration: 1800.0,
+            attack_damage: 8.0,
+            attack_knockback: 6.0,
+            hitbox_radius: 12.0,
+            attack_status: vec![],
+            hurt_increase_alarm: 0.0025,
+            death_increase_alarm: 0.025,
+            difficulty_curve: default(),
+            ranged: None,
+        }
+    }
+}
+
+impl EnemyArchetype {
+    pub const DEFAULT: &'static str = "grunt";
+
+    pub fn apply_actor(&self, actor: &mut Actor) {
+        actor.acceleration = self.acceleration;
+        actor.brake_deceleration = self.brake_deceleration;
+        actor.attack_damage = self.attack_damage;
+        actor.attack_knockback = self.attack_knockback;
+        actor.hitbox_radius = self.hitbox_radius;
+        actor.attack_status = self.attack_status.clone();
+    }
+}
+
+#[derive(Default)]
+struct EnemyArchetypesLoader;
+
+impl AssetLoader for EnemyArchetypesLoader {
+    type Asset = EnemyArchetypes;
+    type Settings = ();
+    type Error = io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = vec![];
+            reader.read_to_end(&mut bytes).await?;
+            ron::de::from_bytes(&bytes).map_err(|e| {
+                let path = load_context.path().display();
+                io::Error::new(io::ErrorKind::InvalidData, format!("{path}:{e}"))
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["archetypes.ron"]
+    }
+}
+
+/// The name of the archetype an enemy was spawned from
+#[derive(Component, Reflect)]
+pub struct Archetype(pub String);
+
+// Health, mass, texture and whether the enemy is ranged only apply on spawn
+fn reload_archetypes(
+    mut asset_events: EventReader<AssetEvent<EnemyArchetypes>>,
+    archetype_assets: Res<ArchetypeAssets>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+    mut enemy_query: Query<
+        (
+            &Archetype,
+            &mut Actor,
+            &mut DifficultyCurve,
+            &mut HurtEffects,
+            &mut DeathEffects,
+            Option<&mut RangedAttack>,
+        ),
+        // Skip corpses
+        With<ActorIntent>,
+    >,
+) {
+    if !asset_events
+        .read()
+        .any(|event| event.is_modified(&archetype_assets.enemies))
+    {
+        return;
+    }
+    let Some(archetypes) = archetypes.get(&archetype_assets.enemies) else {
+        return;
+    };
+
+    info!("Reloading enemy archetypes");
+    for (archetype, mut actor, mut curve, mut hurt, mut death, ranged) in &mut enemy_query {
+        let archetype = archetypes.get(&archetype.0);
+        archetype.apply_actor(&mut actor);
+        *curve = archetype.difficulty_curve.clone();
+        hurt.increase_alarm = archetype.hurt_increase_alarm;
+        death.increase_alarm = archetype.death_increase_alarm;
+        if let (Some(mut ranged), Some(new_ranged)) = (ranged, archetype.ranged) {
+            *ranged = RangedAttack {
+                cooldown_t: ranged.cooldown_t,
+                ..new_ranged
+            };
+        }
+    }
+}
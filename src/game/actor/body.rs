@@ -5,10 +5,12 @@ use bevy::math::vec2;
 use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
 
+use crate::common::audio::SfxChannel;
 use crate::common::PostTransformSet;
 use crate::common::UpdateSet;
 use crate::game::actor::intent::ActorIntent;
 use crate::game::actor::player::PlayerControl;
+use crate::game::noise::NoiseEvent;
 use crate::util::animation::offset::Offset;
 
 pub struct BodyPlugin;
@@ -19,11 +21,11 @@ impl Plugin for BodyPlugin {
 
         app.register_type::<WalkAnimation>()
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     update_walk_animation.in_set(UpdateSet::Start),
                     trigger_walk_animation.in_set(UpdateSet::ApplyIntents),
-                    play_step_sound,
+                    play_step_sound.in_set(UpdateSet::HandleEvents),
                 ),
             )
             .add_systems(
@@ -33,7 +35,7 @@ impl Plugin for BodyPlugin {
 
         app.register_type::<AttackAnimation>()
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     update_attack_animation.in_set(UpdateSet::Start),
                     trigger_attack_animation.in_set(UpdateSet::ApplyIntents),
@@ -46,14 +48,24 @@ impl Plugin for BodyPlugin {
             );
 
         app.register_type::<FlinchAnimation>()
-            .add_systems(Update, update_flinch_animation.in_set(UpdateSet::Start))
+            .add_systems(
+                FixedUpdate,
+                update_flinch_animation.in_set(UpdateSet::Start),
+            )
             .add_systems(
                 PostUpdate,
                 apply_flinch_animation.in_set(PostTransformSet::Blend),
             );
 
+        app.register_type::<RollAnimation>()
+            .add_systems(FixedUpdate, update_roll_animation.in_set(UpdateSet::Start))
+            .add_systems(
+                PostUpdate,
+                apply_roll_animation.in_set(PostTransformSet::Blend),
+            );
+
         app.register_type::<DeathAnimation>()
-            .add_systems(Update, update_death_animation.in_set(UpdateSet::Start))
+            .add_systems(FixedUpdate, update_death_animation.in_set(UpdateSet::Start))
             .add_systems(
                 PostUpdate,
                 apply_death_animation.in_set(PostTransformSet::Blend),
@@ -87,6 +99,7 @@ impl BodyTemplate {
                 },
                 AttackAnimation::default(),
                 FlinchAnimation::default(),
+                RollAnimation::default(),
                 Body,
             ))
             .id();
@@ -146,7 +159,8 @@ fn trigger_walk_animation(
 fn play_step_sound(
     player_query: Query<&GlobalTransform, With<PlayerControl>>,
     animation_query: Query<(&WalkAnimation, &GlobalTransform), Without<PlayerControl>>,
-    audio: Res<Audio>,
+    audio: Res<AudioChannel<SfxChannel>>,
+    mut noise_events: EventWriter<NoiseEvent>,
 ) {
     let Ok(player) = player_query.get_single() else {
         return;
@@ -161,11 +175,14 @@ fn play_step_sound(
 
         let pos = transform.translation().xy();
         let dist_to_player = (player_pos - pos).length() as f64;
-        let max_volume = 0.3;
 
         audio
             .play(sound.clone())
-            .with_volume(max_volume / (0.2 * dist_to_player).max(1.0));
+            .with_volume(SfxChannel::FOOTSTEP / (0.2 * dist_to_player).max(1.0));
+        noise_events.send(NoiseEvent {
+            position: pos,
+            loudness: 60.0,
+        });
     }
 }
 
@@ -213,7 +230,7 @@ fn trigger_attack_animation(
         let Ok(intent) = intent_query.get(parent.get()) else {
             continue;
         };
-        let Some(attack) = intent.attack else {
+        let Some(attack) = intent.attack.or(intent.shoot) else {
             continue;
         };
 
@@ -282,6 +299,49 @@ fn apply_flinch_animation(mut animation_query: Query<(&FlinchAnimation, &mut Tra
     }
 }
 
+#[derive(Component, Reflect)]
+pub struct RollAnimation {
+    pub duration: f32,
+    pub height: f32,
+    pub direction: Vec2,
+    pub t: f32,
+}
+
+impl Default for RollAnimation {
+    fn default() -> Self {
+        Self {
+            duration: 0.3,
+            height: 4.0,
+            direction: Vec2::ZERO,
+            t: 1.0,
+        }
+    }
+}
+
+impl RollAnimation {
+    pub fn trigger(&mut self, direction: Vec2) {
+        self.t = 0.0;
+        self.direction = direction;
+    }
+}
+
+fn update_roll_animation(mut animation_query: Query<&mut RollAnimation>, time: Res<Time>) {
+    let dt = time.delta_seconds();
+
+    for mut anim in &mut animation_query {
+        anim.t = (anim.t + dt / anim.duration).min(1.0);
+    }
+}
+
+fn apply_roll_animation(mut animation_query: Query<(&RollAnimation, &mut Transform)>) {
+    for (anim, mut transform) in &mut animation_query {
+        // Tumble in the direction of the roll
+        let sign = if anim.direction.x < 0.0 { 1.0 } else { -1.0 };
+        transform.translation.y += anim.height * (anim.t * PI).sin();
+        transform.rotation *= Quat::from_rotation_z(sign * anim.t * TAU);
+    }
+}
+
 #[derive(Component, Reflect)]
 pub struct DeathAnimation {
     pub height: f32,
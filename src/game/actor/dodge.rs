@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::common::UpdateSet;
+use crate::game::actor::body::RollAnimation;
+use crate::game::actor::intent::apply_actor_movement;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::actor::Actor;
+
+pub struct DodgePlugin;
+
+impl Plugin for DodgePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Dodge>().add_systems(
+            FixedUpdate,
+            (
+                update_dodge.in_set(UpdateSet::Start),
+                // After movement, so the dash always starts at full speed
+                apply_dodge
+                    .in_set(UpdateSet::ApplyIntents)
+                    .after(apply_actor_movement),
+            ),
+        );
+    }
+}
+
+/// Lets an actor act on `ActorIntent::dodge` with a dash and invulnerability frames
+#[derive(Component, Reflect)]
+pub struct Dodge {
+    /// The speed of the dash impulse
+    pub speed: f32,
+    pub cooldown: f32,
+    pub cooldown_t: f32,
+    /// How long the actor can't be hit for
+    pub invulnerable_time: f32,
+    pub invulnerable_t: f32,
+}
+
+impl Default for Dodge {
+    fn default() -> Self {
+        Self {
+            speed: 320.0,
+            cooldown: 0.8,
+            cooldown_t: 0.0,
+            invulnerable_time: 0.25,
+            invulnerable_t: 0.0,
+        }
+    }
+}
+
+// Restore the hurtbox when the invulnerability frames run out
+fn update_dodge(
+    mut actor_query: Query<(&Actor, &mut Dodge, &mut CollisionGroups)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    for (actor, mut dodge, mut groups) in &mut actor_query {
+        dodge.cooldown_t -= dt;
+        if dodge.invulnerable_t <= 0.0 {
+            continue;
+        }
+
+        dodge.invulnerable_t -= dt;
+        if dodge.invulnerable_t <= 0.0 {
+            let hurtbox_groups = actor.faction.hurtbox_groups();
+            groups.memberships |= hurtbox_groups.memberships;
+        }
+    }
+}
+
+fn apply_dodge(
+    mut actor_query: Query<(
+        &Actor,
+        &ActorIntent,
+        &mut Dodge,
+        &mut Velocity,
+        &mut CollisionGroups,
+        &Children,
+    )>,
+    mut animation_query: Query<&mut RollAnimation>,
+) {
+    for (actor, intent, mut dodge, mut velocity, mut groups, children) in &mut actor_query {
+        let Some(direction) = intent.dodge else {
+            continue;
+        };
+        if dodge.cooldown_t > 0.0 {
+            continue;
+        }
+        dodge.cooldown_t = dodge.cooldown;
+
+        velocity.linvel = dodge.speed * direction;
+
+        // Invulnerability frames
+        dodge.invulnerable_t = dodge.invulnerable_time;
+        let hurtbox_groups = actor.faction.hurtbox_groups();
+        groups.memberships &= !hurtbox_groups.memberships;
+
+        for &child in children {
+            if let Ok(mut anim) = animation_query.get_mut(child) {
+                anim.trigger(direction);
+            }
+        }
+    }
+}
@@ -3,10 +3,14 @@ use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
 use bevy_rapier2d::prelude::*;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
 use rand::Rng;
+use serde::Deserialize;
 
+use crate::common::audio::SfxChannel;
+use crate::common::rng::GameRng;
 use crate::common::UpdateSet;
+use crate::game::actor::archetype::Archetype;
+use crate::game::actor::archetype::EnemyArchetype;
 use crate::game::actor::body::BodyTemplate;
 use crate::game::actor::health::Health;
 use crate::game::actor::intent::ActorIntent;
@@ -15,10 +19,17 @@ use crate::game::actor::Actor;
 use crate::game::actor::ActorAssets;
 use crate::game::actor::ActorBundle;
 use crate::game::alarm::Alarm;
+use crate::game::combat::projectile::RangedAttack;
+use crate::game::combat::status::StatusIconsTemplate;
 use crate::game::combat::DeathEffects;
 use crate::game::combat::Faction;
 use crate::game::combat::HitEvent;
 use crate::game::combat::HurtEffects;
+use crate::game::level::nav_grid::NavGrid;
+use crate::game::level::solid_filter;
+use crate::game::level::Solids;
+use crate::game::noise::NoiseEvent;
+use crate::util::animation::facing::Facing;
 use crate::util::ui::health_bar::HealthBarTemplate;
 use crate::util::ui::nametag::NametagTemplate;
 use crate::util::vfx::AlertPopupTemplate;
@@ -30,15 +41,31 @@ pub struct EnemyPlugin;
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<DifficultyCurve>()
-            .add_systems(Update, apply_difficulty_curve.in_set(UpdateSet::Start));
-
-        app.register_type::<EnemyAi>().add_systems(
-            Update,
-            record_enemy_intents.in_set(UpdateSet::RecordIntents),
+            .add_systems(FixedUpdate, apply_difficulty_curve.in_set(UpdateSet::Start));
+
+        app.register_type::<EnemyAi>()
+            .register_type::<AiState>()
+            .add_event::<AiStateEvent>()
+            .add_event::<ShoutEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    record_enemy_intents.in_set(UpdateSet::RecordIntents),
+                    (play_alert_effects, hear_shouts).in_set(UpdateSet::HandleEvents),
+                ),
+            );
+
+        app.register_type::<Vision>().add_systems(
+            FixedUpdate,
+            update_vision_direction.in_set(UpdateSet::ApplyIntents),
         );
 
-        app.add_event::<AlertEvent>()
-            .add_systems(Update, detect_alert_events.in_set(UpdateSet::Start));
+        app.add_event::<AlertEvent>().add_systems(
+            FixedUpdate,
+            detect_alert_events
+                .in_set(UpdateSet::Start)
+                .after(apply_difficulty_curve),
+        );
     }
 }
 
@@ -117,8 +144,18 @@ fn random_name(mut rng: impl Rng) -> String {
 pub struct EnemyTemplate {
     pub transform: Transform,
     pub name: String,
+    pub archetype: String,
+    /// Defaults to the red gnoll
+    pub texture: Option<Handle<Image>>,
     pub health: f32,
+    pub mass: f32,
+    pub actor: Actor,
+    pub difficulty_curve: DifficultyCurve,
+    /// Ranged enemies shoot instead of attacking in melee
+    pub ranged: Option<RangedAttack>,
     pub is_corpse: bool,
+    /// Patrol points (the first point is the enemy's post)
+    pub patrol: Vec<Entity>,
     pub hurt_increase_alarm: f32,
     pub death_increase_alarm: f32,
 }
@@ -128,8 +165,15 @@ impl Default for EnemyTemplate {
         Self {
             transform: default(),
             name: "Unnamed".to_string(),
+            archetype: EnemyArchetype::DEFAULT.to_string(),
+            texture: None,
             health: 20.0,
+            mass: 1.0,
+            actor: Actor::enemy(),
+            difficulty_curve: default(),
+            ranged: None,
             is_corpse: false,
+            patrol: vec![],
             hurt_increase_alarm: 0.0025,
             death_increase_alarm: 0.025,
         }
@@ -137,14 +181,34 @@ impl Default for EnemyTemplate {
 }
 
 impl EnemyTemplate {
-    pub fn with_random_name(mut self) -> Self {
-        self.name = random_name(thread_rng());
+    pub fn with_archetype(
+        mut self,
+        name: &str,
+        archetype: &EnemyArchetype,
+        ass: &AssetServer,
+    ) -> Self {
+        self.archetype = name.to_string();
+        self.texture = Some(ass.load(&archetype.texture));
+        self.health = archetype.health;
+        self.mass = archetype.mass;
+        archetype.apply_actor(&mut self.actor);
+        self.difficulty_curve = archetype.difficulty_curve.clone();
+        self.ranged = archetype.ranged.clone();
+        self.hurt_increase_alarm = archetype.hurt_increase_alarm;
+        self.death_increase_alarm = archetype.death_increase_alarm;
+        self
+    }
+
+    pub fn with_random_name(mut self, rng: impl Rng) -> Self {
+        self.name = random_name(rng);
         self
     }
 
     pub fn dead(mut self) -> Self {
         self.is_corpse = true;
         self.health = 0.0;
+        self.mass = 25.0;
+        self.actor.brake_deceleration = 700.0;
         self.hurt_increase_alarm = 0.0;
         self.death_increase_alarm = 0.0;
         self
@@ -160,7 +224,9 @@ impl EnemyTemplate {
 
         // Children
         let body = BodyTemplate {
-            texture: actor_assets.gnoll_red.clone(),
+            texture: self
+                .texture
+                .unwrap_or_else(|| actor_assets.gnoll_red.clone()),
             offset: Transform::from_xyz(2.0, 11.0, 0.0),
             step_sound: None,
             is_corpse: self.is_corpse,
@@ -176,13 +242,12 @@ impl EnemyTemplate {
             offset: Transform::from_xyz(0.0, -6.0, 0.0),
         }
         .spawn(commands);
-        let alert_sensor = AlertSensorTemplate.spawn(commands);
+        let status_icons = StatusIconsTemplate {
+            offset: Transform::from_xyz(13.0, -6.0, 0.0),
+        }
+        .spawn(commands);
 
         // Parent
-        let mut actor = Actor::enemy();
-        if self.is_corpse {
-            actor.brake_deceleration = 700.0;
-        }
         let enemy = commands
             .spawn((
                 SpatialBundle {
@@ -191,13 +256,18 @@ impl EnemyTemplate {
                 },
                 ActorBundle {
                     health: Health::full(self.health),
-                    actor,
+                    actor: self.actor,
                     ..default()
                 }
                 .with_faction(FACTION),
-                ColliderMassProperties::Mass(if self.is_corpse { 25.0 } else { 1.0 }),
-                EnemyAi::default(),
-                DifficultyCurve::default(),
+                ColliderMassProperties::Mass(self.mass),
+                EnemyAi {
+                    patrol: self.patrol,
+                    ..default()
+                },
+                Vision::default(),
+                self.difficulty_curve,
+                Archetype(self.archetype),
                 HurtEffects {
                     increase_alarm: self.hurt_increase_alarm,
                     ..default()
@@ -210,9 +280,12 @@ impl EnemyTemplate {
             .add_child(drop_shadow)
             .add_child(nametag)
             .add_child(health_bar)
-            .add_child(alert_sensor)
+            .add_child(status_icons)
             .id();
 
+        if let Some(ranged) = self.ranged {
+            commands.entity(enemy).insert(ranged);
+        }
         if self.is_corpse {
             commands.entity(enemy).remove::<ActorIntent>();
         }
@@ -221,12 +294,36 @@ impl EnemyTemplate {
     }
 }
 
-#[derive(Reflect)]
-struct Curve {
+/// A point on an enemy's patrol route, spawned in level space
+pub struct PatrolPointTemplate {
+    pub transform: Transform,
+}
+
+impl PatrolPointTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("PatrolPoint"),
+                TransformBundle::from_transform(self.transform),
+            ))
+            .id()
+    }
+}
+
+/// A value that scales with `Alarm` (written as `(y0, y1)` in RON)
+#[derive(Reflect, Deserialize, Copy, Clone)]
+#[serde(from = "(f32, f32)")]
+pub struct Curve {
     pub y0: f32,
     pub y1: f32,
 }
 
+impl From<(f32, f32)> for Curve {
+    fn from((y0, y1): (f32, f32)) -> Self {
+        Self::new(y0, y1)
+    }
+}
+
 impl Curve {
     pub fn new(y0: f32, y1: f32) -> Self {
         Self { y0, y1 }
@@ -237,20 +334,34 @@ impl Curve {
     }
 }
 
-#[derive(Component, Reflect)]
-struct DifficultyCurve {
-    speed: Curve,
-    detect_radius: Curve,
-    follow_radius: Curve,
-    attack_radius: Curve,
-    attack_cooldown: Curve,
+#[derive(Component, Reflect, Deserialize, Clone)]
+#[serde(default)]
+pub struct DifficultyCurve {
+    pub speed: Curve,
+    pub vision_range: Curve,
+    /// In degrees
+    pub vision_angle: Curve,
+    pub hearing: Curve,
+    pub investigate_duration: Curve,
+    pub shout_radius: Curve,
+    pub shout_delay: Curve,
+    pub search_duration: Curve,
+    pub follow_radius: Curve,
+    pub attack_radius: Curve,
+    pub attack_cooldown: Curve,
 }
 
 impl Default for DifficultyCurve {
     fn default() -> Self {
         Self {
             speed: Curve::new(60.0, 100.0),
-            detect_radius: Curve::new(0.0, 500.0),
+            vision_range: Curve::new(0.0, 500.0),
+            vision_angle: Curve::new(90.0, 240.0),
+            hearing: Curve::new(1.0, 2.0),
+            investigate_duration: Curve::new(4.0, 8.0),
+            shout_radius: Curve::new(100.0, 300.0),
+            shout_delay: Curve::new(1.0, 0.25),
+            search_duration: Curve::new(3.0, 10.0),
             follow_radius: Curve::new(50.0, 550.0),
             attack_radius: Curve::new(20.0, 25.0),
             attack_cooldown: Curve::new(1.0, 0.5),
@@ -260,185 +371,606 @@ impl Default for DifficultyCurve {
 
 fn apply_difficulty_curve(
     alarm: Res<Alarm>,
-    mut curve_query: Query<(&DifficultyCurve, &mut EnemyAi, &mut Actor, &Children)>,
-    mut detector_query: Query<&mut Transform, With<AlertSensor>>,
+    mut curve_query: Query<(&DifficultyCurve, &mut EnemyAi, &mut Vision, &mut Actor)>,
 ) {
-    for (curve, mut enemy, mut actor, children) in &mut curve_query {
+    for (curve, mut enemy, mut vision, mut actor) in &mut curve_query {
         actor.speed = curve.speed.at(alarm.0);
-        let detect_radius = curve.detect_radius.at(alarm.0);
+        vision.range = curve.vision_range.at(alarm.0);
+        vision.angle = curve.vision_angle.at(alarm.0).to_radians();
+        enemy.hearing = curve.hearing.at(alarm.0);
+        enemy.investigate_duration = curve.investigate_duration.at(alarm.0);
+        enemy.shout_radius = curve.shout_radius.at(alarm.0);
+        enemy.shout_delay = curve.shout_delay.at(alarm.0);
+        enemy.search_duration = curve.search_duration.at(alarm.0);
         enemy.follow_radius = curve.follow_radius.at(alarm.0);
         enemy.attack_radius = curve.attack_radius.at(alarm.0);
         enemy.attack_cooldown = curve.attack_cooldown.at(alarm.0);
+    }
+}
 
-        for &child in children {
-            let Ok(mut transform) = detector_query.get_mut(child) else {
-                continue;
-            };
-            transform.scale = Vec2::splat(detect_radius).extend(1.0);
-        }
+#[derive(Reflect, Copy, Clone, PartialEq, Debug, Default)]
+pub enum AiState {
+    /// Stand at the post
+    #[default]
+    Idle,
+    /// Walk along the patrol route
+    Patrol,
+    /// Walk over to a noise
+    Investigate(Vec2),
+    /// Follow a target, or its last known position when out of sight
+    Chase(Entity),
+    Attack(Entity),
+    /// Look around a position
+    Search(Vec2),
+    /// Walk back to the post (or the current patrol point)
+    ReturnToPost,
+}
+
+impl AiState {
+    pub fn is_hostile(&self) -> bool {
+        matches!(self, Self::Chase(_) | Self::Attack(_))
     }
 }
 
+/// Sent when an enemy's AI state changes
+#[derive(Event)]
+pub struct AiStateEvent {
+    pub enemy: Entity,
+    pub from: AiState,
+    pub to: AiState,
+}
+
 #[derive(Component, Reflect)]
 struct EnemyAi {
+    state: AiState,
+    /// Seconds since entering the current state (or arriving at a patrol point)
+    state_t: f32,
+    /// The first point is the post
+    patrol: Vec<Entity>,
+    patrol_index: usize,
+    /// Seconds to wait at each patrol point
+    patrol_wait: f32,
+    last_known_position: Vec2,
+    /// The remaining waypoints towards the current goal
+    path: Vec<Vec2>,
+    path_goal: Option<IVec2>,
+    /// Multiplier for the distance at which noises can be heard
+    hearing: f32,
+    investigate_duration: f32,
+    search_duration: f32,
+    /// Other enemies within this distance can hear a shout
+    shout_radius: f32,
+    /// Seconds before reacting to a shout
+    shout_delay: f32,
+    shout_increase_alarm: f32,
+    /// The target and last known position from a shout that hasn't been reacted to yet
+    heard_shout: Option<(Entity, Vec2)>,
+    heard_shout_t: f32,
     follow_radius: f32,
     attack_radius: f32,
     attack_cooldown: f32,
     attack_cooldown_t: f32,
-    target: Option<Entity>,
 }
 
 impl Default for EnemyAi {
     fn default() -> Self {
         Self {
+            state: default(),
+            state_t: 0.0,
+            patrol: vec![],
+            patrol_index: 0,
+            patrol_wait: 1.5,
+            last_known_position: Vec2::ZERO,
+            path: vec![],
+            path_goal: None,
+            hearing: 1.0,
+            investigate_duration: 4.0,
+            shout_radius: 100.0,
+            shout_delay: 1.0,
+            shout_increase_alarm: 0.005,
+            heard_shout: None,
+            heard_shout_t: 0.0,
+            search_duration: 3.0,
             follow_radius: 100.0,
             attack_radius: 20.0,
             attack_cooldown: 1.0,
             attack_cooldown_t: 0.5,
-            target: None,
         }
     }
 }
 
+impl EnemyAi {
+    const ARRIVE_RADIUS: f32 = 4.0;
+    const SEARCH_RADIUS: f32 = 48.0;
+
+    fn set_state(&mut self, state: AiState) {
+        if self.state != state {
+            self.state = state;
+            self.state_t = 0.0;
+        }
+    }
+
+    fn chase(&mut self, target: Entity, position: Vec2) {
+        if !self.state.is_hostile() {
+            self.set_state(AiState::Chase(target));
+            self.last_known_position = position;
+        }
+    }
+
+    /// The direction to walk in along a path to the goal, or None if already there
+    fn walk_towards(&mut self, nav_grid: &NavGrid, from: Vec2, to: Vec2) -> Option<Vec2> {
+        if from.distance(to) <= Self::ARRIVE_RADIUS {
+            self.path.clear();
+            self.path_goal = None;
+            return None;
+        }
+
+        let goal = nav_grid.cell(to);
+        if self.path_goal != Some(goal) {
+            // Fall back to a straight line if there's no path
+            self.path = nav_grid.find_path(from, to).unwrap_or_default();
+            self.path.reverse();
+            self.path_goal = Some(goal);
+        }
+        while self
+            .path
+            .last()
+            .is_some_and(|&waypoint| from.distance(waypoint) <= Self::ARRIVE_RADIUS)
+        {
+            self.path.pop();
+        }
+
+        let waypoint = self.path.last().copied().unwrap_or(to);
+        Some((waypoint - from).normalize_or_zero())
+    }
+}
+
 fn record_enemy_intents(
-    mut commands: Commands,
-    mut enemy_query: Query<(&mut EnemyAi, &mut ActorIntent, &GlobalTransform)>,
+    mut enemy_query: Query<(
+        Entity,
+        &mut EnemyAi,
+        &Vision,
+        &mut ActorIntent,
+        Option<&RangedAttack>,
+    )>,
     mut detect_events: EventReader<AlertEvent>,
     mut hit_events: EventReader<HitEvent>,
-    parent_query: Query<&Parent>,
+    mut noise_events: EventReader<NoiseEvent>,
+    mut state_events: EventWriter<AiStateEvent>,
+    mut shout_events: EventWriter<ShoutEvent>,
     player_query: Query<Entity, With<PlayerControl>>,
-    transform_query: Query<&GlobalTransform, Without<EnemyAi>>,
-    actor_assets: Res<ActorAssets>,
-    vfx_assets: Res<VfxAssets>,
+    transform_query: Query<&GlobalTransform>,
+    nav_grid: Res<NavGrid>,
     time: Res<Time>,
-    audio: Res<Audio>,
+    mut rng: ResMut<GameRng>,
 ) {
+    let position_of = |entity: Entity| {
+        transform_query
+            .get(entity)
+            .ok()
+            .map(|gt| gt.translation().xy())
+    };
+
     let Ok(player) = player_query.get_single() else {
-        let mut rng = thread_rng();
-        for (mut enemy, mut intent, _) in &mut enemy_query {
-            if enemy.target.is_none() {
+        for (entity, mut enemy, _, mut intent, _) in &mut enemy_query {
+            if enemy.state == AiState::Idle {
                 continue;
             }
 
-            enemy.target = None;
+            let from = enemy.state;
+            enemy.set_state(AiState::Idle);
+            state_events.send(AiStateEvent {
+                enemy: entity,
+                from,
+                to: enemy.state,
+            });
             intent.attack = None;
-            intent.movement =
-                vec2(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)).normalize_or_zero();
+            intent.shoot = None;
+            // Hostile enemies wander off
+            intent.movement = if from.is_hostile() {
+                vec2(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)).normalize_or_zero()
+            } else {
+                Vec2::ZERO
+            };
         }
         return;
     };
 
-    // Detect target
-    let mut handle_detection = |ai: &mut EnemyAi, enemy: Entity, target: Entity| {
-        if ai.target.is_some() {
-            return;
+    // Collect stimuli
+    let mut alerts = detect_events
+        .read()
+        .map(|event| (event.enemy, event.target))
+        .collect::<Vec<_>>();
+    // Hazards are left to the noise of the hit, which at most draws an investigation
+    alerts.extend(
+        hit_events
+            .read()
+            .filter(|event| event.source == Some(player))
+            .map(|event| (event.hurtbox, player)),
+    );
+    let noises = noise_events
+        .read()
+        .map(|noise| (noise.position, noise.loudness))
+        .collect::<Vec<_>>();
+
+    let dt = time.delta_seconds();
+    for (entity, mut enemy, vision, mut intent, ranged) in &mut enemy_query {
+        let Some(pos) = position_of(entity) else {
+            continue;
+        };
+        let from = enemy.state;
+        enemy.state_t += dt;
+        // Gates have opened or closed
+        if nav_grid.is_changed() {
+            enemy.path_goal = None;
         }
 
-        audio.play(actor_assets.alert.clone()).with_volume(0.6);
-        ai.target = Some(target);
-        let popup = AlertPopupTemplate {
-            offset: Transform::from_xyz(0.0, 38.0, 0.0),
+        // React to stimuli
+        for &(_, target) in alerts.iter().filter(|(alerted, _)| *alerted == entity) {
+            if let Some(target_pos) = position_of(target) {
+                enemy.chase(target, target_pos);
+            }
+        }
+        if let Some((target, target_pos)) = enemy.heard_shout {
+            enemy.heard_shout_t -= dt;
+            if enemy.heard_shout_t <= 0.0 {
+                enemy.heard_shout = None;
+                // Join the chase if the target is close enough to follow
+                if pos.distance(target_pos) <= enemy.follow_radius {
+                    enemy.chase(target, target_pos);
+                } else if !enemy.state.is_hostile() {
+                    enemy.set_state(AiState::Investigate(target_pos));
+                }
+            }
+        }
+        let hearing = enemy.hearing;
+        if !enemy.state.is_hostile() {
+            if let Some(&(noise_pos, _)) = noises
+                .iter()
+                .rev()
+                .find(|(noise_pos, loudness)| pos.distance(*noise_pos) <= loudness * hearing)
+            {
+                enemy.set_state(AiState::Investigate(noise_pos));
+            }
         }
-        .spawn(&mut commands, &vfx_assets);
-        commands.entity(enemy).add_child(popup);
-    };
 
-    for &AlertEvent { sensor, target } in detect_events.read() {
-        let Ok(parent) = parent_query.get(sensor) else {
-            continue;
+        // Run the current state
+        intent.attack = None;
+        intent.shoot = None;
+        let is_ranged = ranged.is_some();
+        let state = enemy.state;
+        intent.movement = match state {
+            AiState::Idle => {
+                if enemy.patrol.len() > 1 {
+                    enemy.set_state(AiState::Patrol);
+                }
+                Vec2::ZERO
+            },
+            AiState::Patrol => {
+                let point = enemy
+                    .patrol
+                    .get(enemy.patrol_index)
+                    .and_then(|&point| position_of(point));
+                match point.map(|point| enemy.walk_towards(&nav_grid, pos, point)) {
+                    Some(Some(direction)) => {
+                        enemy.state_t = 0.0;
+                        0.5 * direction
+                    },
+                    // Wait at the patrol point
+                    Some(None) => {
+                        if enemy.state_t >= enemy.patrol_wait {
+                            enemy.patrol_index = (enemy.patrol_index + 1) % enemy.patrol.len();
+                            enemy.state_t = 0.0;
+                        }
+                        Vec2::ZERO
+                    },
+                    None => {
+                        enemy.set_state(AiState::Idle);
+                        Vec2::ZERO
+                    },
+                }
+            },
+            AiState::Investigate(noise_pos) => {
+                match enemy.walk_towards(&nav_grid, pos, noise_pos) {
+                    Some(direction) if enemy.state_t < enemy.investigate_duration => {
+                        0.5 * direction
+                    },
+                    _ => {
+                        enemy.set_state(AiState::Search(noise_pos));
+                        Vec2::ZERO
+                    },
+                }
+            },
+            AiState::Chase(target) => {
+                let target_pos = position_of(target);
+                let seen = vision.seen == Some(target);
+                if let Some(target_pos) = target_pos.filter(|_| seen) {
+                    enemy.last_known_position = target_pos;
+                }
+
+                let last_known_position = enemy.last_known_position;
+                enemy.attack_cooldown_t = enemy.attack_cooldown / 4.0;
+                match target_pos {
+                    // Give up on target
+                    Some(target_pos) if pos.distance(target_pos) > enemy.follow_radius => {
+                        enemy.set_state(AiState::Search(last_known_position));
+                        Vec2::ZERO
+                    },
+                    // Ranged enemies need a clear shot
+                    Some(target_pos)
+                        if pos.distance(target_pos) <= enemy.attack_radius
+                            && (!is_ranged || seen) =>
+                    {
+                        enemy.set_state(AiState::Attack(target));
+                        (target_pos - pos).normalize_or_zero()
+                    },
+                    Some(_) => {
+                        match enemy.walk_towards(&nav_grid, pos, last_known_position) {
+                            Some(direction) => direction,
+                            // Lost sight of target
+                            None => {
+                                enemy.set_state(AiState::Search(last_known_position));
+                                Vec2::ZERO
+                            },
+                        }
+                    },
+                    None => {
+                        enemy.set_state(AiState::Search(last_known_position));
+                        Vec2::ZERO
+                    },
+                }
+            },
+            AiState::Attack(target) => match position_of(target) {
+                Some(target_pos) => {
+                    enemy.last_known_position = target_pos;
+
+                    let target_delta = target_pos - pos;
+                    let target_direction = target_delta.normalize_or_zero();
+                    if target_delta.length() > enemy.attack_radius
+                        || (is_ranged && vision.seen != Some(target))
+                    {
+                        enemy.set_state(AiState::Chase(target));
+                    } else {
+                        enemy.attack_cooldown_t -= dt;
+                        if enemy.attack_cooldown_t <= 0.0 {
+                            if is_ranged {
+                                intent.shoot = Some(target_direction);
+                            } else {
+                                intent.attack = Some(target_direction);
+                            }
+                            enemy.attack_cooldown_t = enemy.attack_cooldown;
+                        }
+                    }
+                    // Ranged enemies hold their ground
+                    if is_ranged {
+                        Vec2::ZERO
+                    } else {
+                        target_direction
+                    }
+                },
+                None => {
+                    let last_known_position = enemy.last_known_position;
+                    enemy.set_state(AiState::Search(last_known_position));
+                    Vec2::ZERO
+                },
+            },
+            AiState::Search(search_pos) => {
+                if enemy.state_t >= enemy.search_duration {
+                    enemy.set_state(AiState::ReturnToPost);
+                    Vec2::ZERO
+                } else if pos.distance(search_pos) > EnemyAi::SEARCH_RADIUS {
+                    enemy
+                        .walk_towards(&nav_grid, pos, search_pos)
+                        .map_or(Vec2::ZERO, |direction| 0.5 * direction)
+                } else if intent.movement == Vec2::ZERO || rng.gen_ratio(1, 30) {
+                    0.5 * vec2(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0))
+                        .normalize_or_zero()
+                } else {
+                    intent.movement
+                }
+            },
+            AiState::ReturnToPost => {
+                let point = enemy
+                    .patrol
+                    .get(enemy.patrol_index)
+                    .and_then(|&point| position_of(point));
+                match point.and_then(|point| enemy.walk_towards(&nav_grid, pos, point)) {
+                    Some(direction) => 0.5 * direction,
+                    None => {
+                        enemy.set_state(AiState::Idle);
+                        Vec2::ZERO
+                    },
+                }
+            },
         };
-        if let Ok((mut enemy, ..)) = enemy_query.get_mut(parent.get()) {
-            handle_detection(&mut enemy, parent.get(), target);
+
+        if enemy.state != from {
+            state_events.send(AiStateEvent {
+                enemy: entity,
+                from,
+                to: enemy.state,
+            });
         }
-    }
-    for &HitEvent { hurtbox, .. } in hit_events.read() {
-        if let Ok((mut enemy, ..)) = enemy_query.get_mut(hurtbox) {
-            // Assume the hitbox originated from the player
-            handle_detection(&mut enemy, hurtbox, player);
+        // Call for help on gaining a target
+        match enemy.state {
+            AiState::Chase(target) if !from.is_hostile() => {
+                shout_events.send(ShoutEvent {
+                    enemy: entity,
+                    target,
+                    position: enemy.last_known_position,
+                });
+            },
+            _ => {},
         }
     }
+}
 
-    let dt = time.delta_seconds();
-    for (mut enemy, mut intent, actor_gt) in &mut enemy_query {
-        let Some(target) = enemy.target else { continue };
-        let Ok(target_gt) = transform_query.get(target) else {
+/// Sent when an enemy gains a target and calls for help
+#[derive(Event)]
+pub struct ShoutEvent {
+    pub enemy: Entity,
+    pub target: Entity,
+    /// The target's last known position
+    pub position: Vec2,
+}
+
+fn hear_shouts(
+    mut shout_events: EventReader<ShoutEvent>,
+    mut enemy_query: Query<(Entity, &mut EnemyAi, &GlobalTransform), With<ActorIntent>>,
+    mut alarm: ResMut<Alarm>,
+    rapier_context: Res<RapierContext>,
+    solids: Solids,
+) {
+    let is_opaque = |entity| solids.contains(entity);
+    let filter = solid_filter(&is_opaque);
+
+    for &ShoutEvent {
+        enemy: shouter,
+        target,
+        position,
+    } in shout_events.read()
+    {
+        let Ok((_, shouter_ai, shouter_gt)) = enemy_query.get(shouter) else {
             continue;
         };
+        let origin = shouter_gt.translation().xy();
+        let radius = shouter_ai.shout_radius;
+        let delay = shouter_ai.shout_delay;
+        alarm.increase(shouter_ai.shout_increase_alarm);
+
+        for (entity, mut enemy, gt) in &mut enemy_query {
+            let listener_pos = gt.translation().xy();
+            if entity == shouter
+                || enemy.state.is_hostile()
+                || enemy.heard_shout.is_some()
+                || origin.distance(listener_pos) > radius
+                || !is_in_line_of_sight(&rapier_context, filter, origin, listener_pos)
+            {
+                continue;
+            }
 
-        intent.attack = None;
-        intent.movement = Vec2::ZERO;
-
-        let target_delta = target_gt.translation().xy() - actor_gt.translation().xy();
-        let target_distance = target_delta.length();
+            enemy.heard_shout = Some((target, position));
+            enemy.heard_shout_t = delay;
+        }
+    }
+}
 
-        // Give up on target
-        if target_distance > enemy.follow_radius {
-            enemy.target = None;
+// Gaining a target comes with a shout (see ShoutEvent)
+fn play_alert_effects(
+    mut commands: Commands,
+    mut state_events: EventReader<AiStateEvent>,
+    actor_assets: Res<ActorAssets>,
+    vfx_assets: Res<VfxAssets>,
+    audio: Res<AudioChannel<SfxChannel>>,
+) {
+    for &AiStateEvent { enemy, from, to } in state_events.read() {
+        if from.is_hostile() || !to.is_hostile() {
             continue;
         }
 
-        // Move towards target
-        let target_direction = target_delta.normalize();
-        intent.movement = target_direction;
+        audio
+            .play(actor_assets.alert.clone())
+            .with_volume(SfxChannel::ALERT);
+        let popup = AlertPopupTemplate {
+            offset: Transform::from_xyz(0.0, 38.0, 0.0),
+        }
+        .spawn(&mut commands, &vfx_assets);
+        commands.entity(enemy).add_child(popup);
+    }
+}
 
-        // Attack target
-        if target_distance <= enemy.attack_radius {
-            enemy.attack_cooldown_t -= dt;
-            if enemy.attack_cooldown_t <= 0.0 {
-                intent.attack = Some(target_direction);
-                enemy.attack_cooldown_t = enemy.attack_cooldown;
-            }
-        } else {
-            enemy.attack_cooldown_t = enemy.attack_cooldown / 4.0;
+/// A view cone that is blocked by walls and closed gates
+#[derive(Component, Reflect)]
+pub struct Vision {
+    pub range: f32,
+    /// The full angle of the cone (in radians)
+    pub angle: f32,
+    pub direction: Vec2,
+    /// The target currently in sight
+    pub seen: Option<Entity>,
+}
+
+impl Default for Vision {
+    fn default() -> Self {
+        Self {
+            range: 0.0,
+            angle: 90f32.to_radians(),
+            direction: Vec2::X,
+            seen: None,
         }
     }
 }
 
+impl Vision {
+    fn contains(&self, delta: Vec2) -> bool {
+        let distance = delta.length();
+        distance <= self.range
+            && (distance == 0.0 || self.direction.angle_between(delta).abs() <= self.angle / 2.0)
+    }
+}
+
+// Look where the enemy is walking, or straight ahead when standing still
+fn update_vision_direction(mut vision_query: Query<(&mut Vision, &ActorIntent, &Facing)>) {
+    for (mut vision, intent, facing) in &mut vision_query {
+        vision.direction = if intent.movement != Vec2::ZERO {
+            intent.movement.normalize()
+        } else if facing.left() {
+            Vec2::NEG_X
+        } else {
+            Vec2::X
+        };
+    }
+}
+
+/// Sent when an enemy catches sight of a target
 #[derive(Event)]
 pub struct AlertEvent {
-    pub sensor: Entity,
+    pub enemy: Entity,
     pub target: Entity,
 }
 
 fn detect_alert_events(
-    mut collision_events: EventReader<CollisionEvent>,
     mut detect_events: EventWriter<AlertEvent>,
-    detector_query: Query<(), With<AlertSensor>>,
+    rapier_context: Res<RapierContext>,
+    // Corpses don't have an intent, and can't see
+    mut enemy_query: Query<(Entity, &mut Vision, &GlobalTransform), With<ActorIntent>>,
+    target_query: Query<(Entity, &Actor, &GlobalTransform)>,
+    solids: Solids,
 ) {
-    for &event in collision_events.read() {
-        let CollisionEvent::Started(entity1, entity2, _) = event else {
-            continue;
-        };
-
-        let mut handle_collision = |sensor: Entity, target: Entity| {
-            if detector_query.contains(sensor) {
-                detect_events.send(AlertEvent { sensor, target });
-            }
-        };
-
-        handle_collision(entity1, entity2);
-        handle_collision(entity2, entity1);
+    // Only walls and closed gates block line of sight
+    let is_opaque = |entity| solids.contains(entity);
+    let filter = solid_filter(&is_opaque);
+
+    for (enemy, mut vision, enemy_gt) in &mut enemy_query {
+        let origin = enemy_gt.translation().xy();
+        let seen = target_query
+            .iter()
+            .filter(|(_, actor, _)| matches!(actor.faction, Faction::Player))
+            .find(|(_, _, target_gt)| {
+                let target_pos = target_gt.translation().xy();
+                vision.contains(target_pos - origin)
+                    && is_in_line_of_sight(&rapier_context, filter, origin, target_pos)
+            })
+            .map(|(target, ..)| target);
+
+        if let Some(target) = seen.filter(|&target| vision.seen != Some(target)) {
+            detect_events.send(AlertEvent { enemy, target });
+        }
+        vision.seen = seen;
     }
 }
 
-#[derive(Component, Reflect)]
-pub struct AlertSensor;
-
-pub struct AlertSensorTemplate;
-
-impl AlertSensorTemplate {
-    pub fn spawn(self, commands: &mut Commands) -> Entity {
-        commands
-            .spawn((
-                Name::new("AlertSensor"),
-                TransformBundle::default(),
-                Collider::ball(1.0),
-                ColliderMassProperties::Mass(0.0),
-                Sensor,
-                Faction::Enemy.hitbox_groups(),
-                ActiveEvents::COLLISION_EVENTS,
-                AlertSensor,
-            ))
-            .id()
-    }
+/// Whether the line between two points is clear (according to the filter)
+fn is_in_line_of_sight(
+    rapier_context: &RapierContext,
+    filter: QueryFilter,
+    from: Vec2,
+    to: Vec2,
+) -> bool {
+    let delta = to - from;
+    let distance = delta.length();
+    distance == 0.0
+        || rapier_context
+            .cast_ray(from, delta / distance, distance, true, filter)
+            .is_none()
 }
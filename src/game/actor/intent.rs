@@ -4,6 +4,7 @@ use bevy_rapier2d::prelude::*;
 use crate::common::UpdateSet;
 use crate::game::actor::body::AttackAnimation;
 use crate::game::actor::Actor;
+use crate::game::combat::status::StatusEffects;
 use crate::util::animation::facing::Facing;
 use crate::util::math::MoveTowards;
 
@@ -12,7 +13,7 @@ pub struct IntentPlugin;
 impl Plugin for IntentPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<ActorIntent>().add_systems(
-            Update,
+            FixedUpdate,
             (
                 set_actor_facing.in_set(UpdateSet::ApplyIntents),
                 apply_actor_movement.in_set(UpdateSet::ApplyIntents),
@@ -25,6 +26,14 @@ impl Plugin for IntentPlugin {
 pub struct ActorIntent {
     pub movement: Vec2,
     pub attack: Option<Vec2>,
+    /// Requires a `RangedAttack`
+    pub shoot: Option<Vec2>,
+    /// Requires a `Dodge`
+    pub dodge: Option<Vec2>,
+    /// Interact with the closest `Interactable` in range
+    pub interact: bool,
+    /// Drink a potion from the actor's `Inventory`
+    pub drink: bool,
 }
 
 fn set_actor_facing(
@@ -32,11 +41,12 @@ fn set_actor_facing(
     attack_animation_query: Query<&AttackAnimation>,
 ) {
     for (intent, children, mut facing) in &mut actor_query {
-        if intent.movement.x == 0.0 && intent.attack.is_none() {
+        let attack = intent.attack.or(intent.shoot);
+        if intent.movement.x == 0.0 && attack.is_none() {
             continue;
         }
 
-        *facing = if intent.attack.map(|dir| dir.x < 0.0).unwrap_or_else(|| {
+        *facing = if attack.map(|dir| dir.x < 0.0).unwrap_or_else(|| {
             children
                 .into_iter()
                 .flatten()
@@ -57,12 +67,17 @@ fn set_actor_facing(
     }
 }
 
-fn apply_actor_movement(
-    mut actor_query: Query<(&Actor, &mut Velocity, Option<&ActorIntent>)>,
+pub fn apply_actor_movement(
+    mut actor_query: Query<(
+        &Actor,
+        &mut Velocity,
+        Option<&ActorIntent>,
+        Option<&StatusEffects>,
+    )>,
     time: Res<Time>,
 ) {
     let dt = time.delta_seconds();
-    for (actor, mut velocity, intent) in &mut actor_query {
+    for (actor, mut velocity, intent, status) in &mut actor_query {
         let (intent_direction, intent_magnitude) = if let Some(intent) = intent {
             (
                 intent.movement.normalize_or_zero(),
@@ -78,7 +93,8 @@ fn apply_actor_movement(
             actor.acceleration
         };
 
-        let target_velocity = intent_direction * intent_magnitude * actor.speed;
+        let speed = actor.speed * status.map_or(1.0, StatusEffects::speed_scale);
+        let target_velocity = intent_direction * intent_magnitude * speed;
         velocity.linvel = velocity
             .linvel
             .move_towards(target_velocity, acceleration * dt);
@@ -1,20 +1,29 @@
 use bevy::prelude::*;
+use bevy::utils::HashSet;
 use bevy::window::PrimaryWindow;
 use bevy_rapier2d::prelude::*;
+use leafwing_input_manager::plugin::InputManagerSystem;
 use leafwing_input_manager::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
 
 use crate::common::camera::GameCamera;
 use crate::common::UpdateSet;
 use crate::game::actor::body::Body;
 use crate::game::actor::body::BodyTemplate;
+use crate::game::actor::dodge::Dodge;
 use crate::game::actor::health::Health;
 use crate::game::actor::intent::ActorIntent;
 use crate::game::actor::Actor;
 use crate::game::actor::ActorAssets;
 use crate::game::actor::ActorBundle;
 use crate::game::alarm::Alarm;
+use crate::game::combat::projectile::RangedAttack;
+use crate::game::combat::status::StatusIconsTemplate;
+use crate::game::combat::DeathEvent;
 use crate::game::combat::Faction;
 use crate::game::combat::HurtEffects;
+use crate::game::inventory::Inventory;
 use crate::game::level::plate::Plate;
 use crate::util::ui::health_bar::HealthBarTemplate;
 use crate::util::ui::nametag::NametagTemplate;
@@ -26,14 +35,24 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<PlayerAction>()
-            .add_plugins(InputManagerPlugin::<PlayerAction>::default());
+            .add_plugins(InputManagerPlugin::<PlayerAction>::default())
+            .add_systems(
+                PreUpdate,
+                aim_with_cursor.in_set(InputManagerSystem::ManualControl),
+            );
 
         app.register_type::<Playthrough>()
             .init_resource::<Playthrough>()
-            .add_systems(Update, detect_defection.in_set(UpdateSet::Start));
+            .add_systems(
+                FixedUpdate,
+                (
+                    detect_defection.in_set(UpdateSet::Start),
+                    count_kills.in_set(UpdateSet::HandleEvents),
+                ),
+            );
 
         app.register_type::<PlayerControl>().add_systems(
-            Update,
+            FixedUpdate,
             record_player_intent.in_set(UpdateSet::RecordIntents),
         );
     }
@@ -41,25 +60,34 @@ impl Plugin for PlayerPlugin {
 
 const PLAYER_NAME: &str = "Sai";
 
-#[derive(Actionlike, Reflect, Clone, Hash, PartialEq, Eq)]
-enum PlayerAction {
+#[derive(Actionlike, Reflect, Serialize, Deserialize, Clone, Hash, PartialEq, Eq, Debug)]
+pub enum PlayerAction {
     Move,
     Aim,
     Attack,
+    Throw,
+    Dodge,
+    Interact,
+    Drink,
 }
 
 #[derive(Resource, Reflect, Default)]
 #[reflect(Resource)]
 pub struct Playthrough {
     pub defected: bool,
+    /// When the player defected
     pub start_time: f32,
+    /// When the level was entered
+    pub run_start_time: f32,
     pub health: Option<f32>,
+    pub inventory: Option<Inventory>,
+    pub kills: u32,
 }
 
 fn detect_defection(
     mut collision_events: EventReader<CollisionEvent>,
     plate_query: Query<(), With<Plate>>,
-    player_query: Query<&Children, With<PlayerControl>>,
+    player_query: Query<(Entity, &Children), With<PlayerControl>>,
     mut body_query: Query<&mut Handle<Image>, With<Body>>,
     mut playthrough: ResMut<Playthrough>,
     mut alarm: ResMut<Alarm>,
@@ -69,7 +97,7 @@ fn detect_defection(
     if playthrough.defected {
         return;
     }
-    let Ok(children) = player_query.get_single() else {
+    let Ok((player, children)) = player_query.get_single() else {
         return;
     };
 
@@ -78,7 +106,15 @@ fn detect_defection(
             continue;
         };
 
-        if !plate_query.contains(entity1) && !plate_query.contains(entity2) {
+        // Plates can also be pressed by enemies and corpses
+        let other = if entity1 == player {
+            entity2
+        } else if entity2 == player {
+            entity1
+        } else {
+            continue;
+        };
+        if !plate_query.contains(other) {
             continue;
         }
 
@@ -96,30 +132,100 @@ fn detect_defection(
     }
 }
 
+fn count_kills(
+    mut death_events: EventReader<DeathEvent>,
+    actor_query: Query<&Actor>,
+    mut playthrough: ResMut<Playthrough>,
+) {
+    for &DeathEvent(entity) in death_events.read() {
+        if actor_query
+            .get(entity)
+            .is_ok_and(|actor| matches!(actor.faction, Faction::Enemy))
+        {
+            playthrough.kills += 1;
+        }
+    }
+}
+
 #[derive(Component, Reflect, Default)]
 pub struct PlayerControl {
     pub deny_input: bool,
+    /// The actions that were pressed on the previous fixed timestep
+    pub held: HashSet<PlayerAction>,
 }
 
-fn record_player_intent(
-    mut player_query: Query<(
-        &ActionState<PlayerAction>,
-        &mut ActorIntent,
-        &GlobalTransform,
-        &PlayerControl,
-    )>,
+impl PlayerControl {
+    /// `just_pressed` is per frame, so detect presses per fixed timestep instead
+    fn just_pressed(
+        &mut self,
+        action: &ActionState<PlayerAction>,
+        action_type: PlayerAction,
+    ) -> bool {
+        if action.pressed(&action_type) {
+            // Only the first tick of a press inserts a new entry
+            self.held.insert(action_type)
+        } else {
+            self.held.remove(&action_type);
+            false
+        }
+    }
+}
+
+// Resolve the cursor into the Aim axis, so that aim is fully described by the ActionState
+fn aim_with_cursor(
+    mut player_query: Query<
+        (&mut ActionState<PlayerAction>, &GlobalTransform),
+        With<PlayerControl>,
+    >,
     primary_window_query: Query<&Window, With<PrimaryWindow>>,
     camera: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
 ) {
+    let Ok((mut action, player_gt)) = player_query.get_single_mut() else {
+        return;
+    };
+    // Aiming with the right stick takes priority
+    if action
+        .clamped_axis_pair(&PlayerAction::Aim)
+        .is_some_and(|axis_pair| axis_pair.xy() != Vec2::ZERO)
+    {
+        return;
+    }
     let Ok(window) = primary_window_query.get_single() else {
         return;
     };
     let Ok((camera, cam_gt)) = camera.get_single() else {
         return;
     };
-    let Ok((action, mut intent, player_gt, player)) = player_query.get_single_mut() else {
+    let Some(cursor_pos) = window
+        .cursor_position()
+        .and_then(|p| camera.viewport_to_world_2d(cam_gt, p))
+    else {
+        return;
+    };
+
+    let direction = (cursor_pos - player_gt.translation().xy()).normalize_or_zero();
+    action
+        .action_data_mut_or_default(&PlayerAction::Aim)
+        .axis_pair = Some(DualAxisData::from_xy(direction));
+}
+
+fn record_player_intent(
+    mut player_query: Query<(
+        &ActionState<PlayerAction>,
+        &mut ActorIntent,
+        &mut PlayerControl,
+    )>,
+) {
+    let Ok((action, mut intent, mut player)) = player_query.get_single_mut() else {
         return;
     };
+
+    let attack_just_pressed = player.just_pressed(action, PlayerAction::Attack);
+    let throw_just_pressed = player.just_pressed(action, PlayerAction::Throw);
+    let dodge_just_pressed = player.just_pressed(action, PlayerAction::Dodge);
+    let interact_just_pressed = player.just_pressed(action, PlayerAction::Interact);
+    let drink_just_pressed = player.just_pressed(action, PlayerAction::Drink);
+
     if player.deny_input {
         return;
     }
@@ -140,16 +246,23 @@ fn record_player_intent(
     }
 
     intent.attack = None;
-    if action.just_pressed(&PlayerAction::Attack) {
-        intent.attack = aim
-            .or_else(|| {
-                window
-                    .cursor_position()
-                    .and_then(|p| camera.viewport_to_world_2d(cam_gt, p))
-                    .map(|p| p - player_gt.translation().xy())
-            })
+    if attack_just_pressed {
+        intent.attack = aim.map(|d| d.normalize());
+    }
+    intent.shoot = None;
+    if throw_just_pressed {
+        intent.shoot = aim.map(|d| d.normalize());
+    }
+    // Dodge in the direction of movement, or towards the aim when standing still
+    intent.dodge = None;
+    if dodge_just_pressed {
+        intent.dodge = Some(intent.movement)
+            .filter(|&movement| movement != Vec2::ZERO)
+            .or(aim)
             .map(|d| d.normalize());
     }
+    intent.interact = interact_just_pressed;
+    intent.drink = drink_just_pressed;
 }
 
 pub struct PlayerTemplate {
@@ -157,6 +270,7 @@ pub struct PlayerTemplate {
     pub texture: Handle<Image>,
     pub current_health: f32,
     pub max_health: f32,
+    pub inventory: Inventory,
 }
 
 impl Default for PlayerTemplate {
@@ -166,6 +280,7 @@ impl Default for PlayerTemplate {
             texture: default(),
             current_health: 200.0,
             max_health: 200.0,
+            inventory: Inventory::starting(),
         }
     }
 }
@@ -197,6 +312,10 @@ impl PlayerTemplate {
             offset: Transform::from_xyz(0.0, -6.0, 0.0),
         }
         .spawn(commands);
+        let status_icons = StatusIconsTemplate {
+            offset: Transform::from_xyz(13.0, -6.0, 0.0),
+        }
+        .spawn(commands);
 
         // Parent
         commands
@@ -220,23 +339,18 @@ impl PlayerTemplate {
                     sound: Some(actor_assets.hurt.clone()),
                     ..default()
                 },
-                InputManagerBundle::<PlayerAction> {
-                    input_map: InputMap::default()
-                        .insert(PlayerAction::Move, VirtualDPad::wasd())
-                        .insert(PlayerAction::Move, VirtualDPad::arrow_keys())
-                        .insert(PlayerAction::Move, DualAxis::left_stick())
-                        .insert(PlayerAction::Aim, DualAxis::right_stick())
-                        .insert(PlayerAction::Attack, GamepadButtonType::RightTrigger)
-                        .insert(PlayerAction::Attack, MouseButton::Left)
-                        .build(),
-                    ..default()
-                },
+                // The InputMap is filled in from Keybindings
+                InputManagerBundle::<PlayerAction>::default(),
                 PlayerControl::default(),
+                RangedAttack::default(),
+                Dodge::default(),
+                self.inventory,
             ))
             .add_child(body)
             .add_child(drop_shadow)
             .add_child(nametag)
             .add_child(health_bar)
+            .add_child(status_icons)
             .id()
     }
 }
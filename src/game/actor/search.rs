@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+use crate::common::audio::SfxChannel;
+use crate::common::UpdateSet;
+use crate::game::actor::ActorAssets;
+use crate::game::interact::InteractEvent;
+use crate::game::interact::Interactable;
+use crate::game::inventory::Inventory;
+use crate::game::inventory::Item;
+
+pub struct SearchPlugin;
+
+impl Plugin for SearchPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Searchable>()
+            .add_systems(FixedUpdate, search_corpses.in_set(UpdateSet::HandleEvents));
+    }
+}
+
+/// A corpse that can be searched once for the key it carries
+#[derive(Component, Reflect, Default)]
+pub struct Searchable {
+    pub key: Option<String>,
+}
+
+fn search_corpses(
+    mut commands: Commands,
+    mut interact_events: EventReader<InteractEvent>,
+    mut searchable_query: Query<&mut Searchable>,
+    mut inventory_query: Query<&mut Inventory>,
+    actor_assets: Res<ActorAssets>,
+    audio: Res<AudioChannel<SfxChannel>>,
+) {
+    for &InteractEvent { actor, target } in interact_events.read() {
+        let Ok(mut searchable) = searchable_query.get_mut(target) else {
+            continue;
+        };
+        commands
+            .entity(target)
+            .remove::<(Searchable, Interactable)>();
+
+        let Some(key) = searchable.key.take() else {
+            continue;
+        };
+        let Ok(mut inventory) = inventory_query.get_mut(actor) else {
+            continue;
+        };
+        inventory.add(Item::Key(key), 1);
+        audio
+            .play(actor_assets.loot.clone())
+            .with_volume(SfxChannel::ITEM);
+    }
+}
@@ -1,3 +1,6 @@
+pub mod projectile;
+pub mod status;
+
 use std::f32::consts::PI;
 
 use bevy::prelude::*;
@@ -5,7 +8,9 @@ use bevy_asset_loader::prelude::*;
 use bevy_kira_audio::prelude::*;
 use bevy_rapier2d::prelude::*;
 
+use crate::common::audio::SfxChannel;
 use crate::common::UpdateSet;
+use crate::game::actor::body::AttackAnimation;
 use crate::game::actor::body::DeathAnimation;
 use crate::game::actor::body::FlinchAnimation;
 use crate::game::actor::body::WalkAnimation;
@@ -13,6 +18,9 @@ use crate::game::actor::health::Health;
 use crate::game::actor::intent::ActorIntent;
 use crate::game::actor::Actor;
 use crate::game::alarm::Alarm;
+use crate::game::combat::status::StatusEffect;
+use crate::game::combat::status::StatusEffects;
+use crate::game::noise::NoiseEvent;
 use crate::util::DespawnSet;
 
 pub struct CombatPlugin;
@@ -22,27 +30,38 @@ impl Plugin for CombatPlugin {
         app.register_type::<CombatAssets>()
             .init_collection::<CombatAssets>();
 
-        app.add_systems(
-            Update,
+        app.add_plugins((projectile::ProjectilePlugin, status::StatusPlugin));
+
+        app.register_type::<Hitbox>().add_systems(
+            FixedUpdate,
             (
                 spawn_attack_hitboxes.in_set(UpdateSet::ApplyIntents),
-                clean_up_hitboxes.in_set(UpdateSet::Update),
+                // Decide hit or miss after this frame's hits have been applied
+                update_hitboxes
+                    .in_set(UpdateSet::HandleEvents)
+                    .after(apply_hit_effects),
             ),
         );
 
         app.add_event::<HitEvent>()
-            .add_systems(Update, detect_hit_events.in_set(UpdateSet::Start));
+            .add_systems(FixedUpdate, detect_hit_events.in_set(UpdateSet::Start));
 
-        app.register_type::<HitEffects>()
-            .add_systems(Update, apply_hit_effects.in_set(UpdateSet::HandleEvents));
+        app.register_type::<HitEffects>().add_systems(
+            FixedUpdate,
+            apply_hit_effects.in_set(UpdateSet::HandleEvents),
+        );
 
-        app.register_type::<HurtEffects>()
-            .add_systems(Update, apply_hurt_effects.in_set(UpdateSet::HandleEvents));
+        app.register_type::<HurtEffects>().add_systems(
+            FixedUpdate,
+            apply_hurt_effects.in_set(UpdateSet::HandleEvents),
+        );
 
         app.add_event::<DeathEvent>();
 
-        app.register_type::<DeathEffects>()
-            .add_systems(Update, apply_death_effects.in_set(UpdateSet::HandleEvents));
+        app.register_type::<DeathEffects>().add_systems(
+            FixedUpdate,
+            apply_death_effects.in_set(UpdateSet::HandleEvents),
+        );
     }
 }
 
@@ -88,65 +107,130 @@ impl Faction {
     }
 }
 
+/// Hitbox groups for level hazards, which hurt every faction
+pub fn hazard_hitbox_groups() -> CollisionGroups {
+    CollisionGroups {
+        memberships: HITBOX_GROUP,
+        filters: PLAYER_HURTBOX_GROUP | ENEMY_HURTBOX_GROUP,
+    }
+}
+
 pub struct HitboxTemplate {
-    pub position: Vec3,
+    pub owner: Entity,
+    /// The owner's body, whose `AttackAnimation` the hitbox follows
+    pub body: Entity,
+    pub origin: Vec3,
     pub direction: Vec2,
+    pub distance: f32,
     pub radius: f32,
     pub damage: f32,
     pub knockback: f32,
+    pub status: Vec<StatusEffect>,
     pub faction: Faction,
+    pub startup: f32,
+    pub recovery: f32,
+    pub arc: f32,
 }
 
 impl HitboxTemplate {
     pub fn spawn(self, commands: &mut Commands, combat_assets: &CombatAssets) -> Entity {
+        let hitbox = Hitbox {
+            owner: self.owner,
+            body: self.body,
+            direction: self.direction,
+            distance: self.distance,
+            arc: self.arc,
+            startup: self.startup,
+            recovery: self.recovery,
+            t: 0.0,
+        };
+
         commands
             .spawn((
                 Name::new("Hitbox"),
                 TransformBundle {
                     local: Transform {
-                        translation: self.position,
+                        translation: hitbox.position(self.origin, 0.0),
                         ..default()
                     },
                     ..default()
                 },
                 Collider::ball(self.radius),
                 Sensor,
+                // Enabled during the active window
+                ColliderDisabled,
                 self.faction.hitbox_groups(),
                 ActiveEvents::COLLISION_EVENTS,
                 HitEffects {
                     damage: self.damage,
                     knockback: self.knockback * self.direction,
+                    status: self.status,
                     success_sound: Some(combat_assets.attack_hit.clone()),
                     failure_sound: Some(combat_assets.attack_miss.clone()),
+                    source: Some(self.owner),
                     ..default()
                 },
+                hitbox,
             ))
             .id()
     }
 }
 
+/// A melee hitbox with a startup, active and recovery timeline along its body's `AttackAnimation`
+#[derive(Component, Reflect)]
+pub struct Hitbox {
+    pub owner: Entity,
+    pub body: Entity,
+    pub direction: Vec2,
+    pub distance: f32,
+    /// The angle swept over the active window, in radians
+    pub arc: f32,
+    /// The animation time when the active window starts
+    pub startup: f32,
+    /// The animation time when the active window ends
+    pub recovery: f32,
+    /// The animation time on the previous frame
+    pub t: f32,
+}
+
+impl Hitbox {
+    /// The position along the swing arc, given the progress through the active window
+    fn position(&self, origin: Vec3, progress: f32) -> Vec3 {
+        let direction = Vec2::from_angle(self.arc * (0.5 - progress)).rotate(self.direction);
+        // Make the hitbox offset slightly ovular
+        let ovular_dir = Quat::from_rotation_x(0.5 * PI * 0.3) * direction.extend(0.0);
+        origin + self.distance * ovular_dir
+    }
+}
+
 pub fn spawn_attack_hitboxes(
     mut commands: Commands,
     combat_assets: Res<CombatAssets>,
-    actor_query: Query<(&Actor, &GlobalTransform, &ActorIntent)>,
+    actor_query: Query<(Entity, &Actor, &GlobalTransform, &ActorIntent, &Children)>,
+    body_query: Query<(), With<AttackAnimation>>,
 ) {
-    for (actor, gt, intent) in &actor_query {
+    for (entity, actor, gt, intent, children) in &actor_query {
         let Some(direction) = intent.attack else {
             continue;
         };
-
-        // Make the hitbox offset slightly ovular
-        let ovular_dir = Quat::from_rotation_x(0.5 * PI * 0.3) * direction.extend(0.0);
-        let radius = 12.0;
-        let distance = radius;
+        let Some(&body) = children.iter().find(|&&child| body_query.contains(child)) else {
+            continue;
+        };
 
         HitboxTemplate {
-            position: gt.translation() + distance * ovular_dir,
+            owner: entity,
+            body,
+            origin: gt.translation(),
             direction,
-            radius,
-            damage: 8.0,
-            knockback: 6.0,
+            distance: actor.hitbox_radius,
+            radius: actor.hitbox_radius,
+            damage: actor.attack_damage,
+            knockback: actor.attack_knockback,
+            status: actor.attack_status.clone(),
             faction: actor.faction,
+            startup: 0.0,
+            recovery: 0.5,
+            arc: PI / 3.0,
         }
         .spawn(&mut commands, &combat_assets);
     }
@@ -156,12 +240,15 @@ pub fn spawn_attack_hitboxes(
 pub struct HitEvent {
     pub hitbox: Entity,
     pub hurtbox: Entity,
+    /// The actor behind the hit, if any
+    pub source: Option<Entity>,
 }
 
+// Each hitbox hits each target at most once
 fn detect_hit_events(
     mut collision_events: EventReader<CollisionEvent>,
     mut hit_events: EventWriter<HitEvent>,
-    hit_query: Query<&HitEffects>,
+    mut hit_query: Query<&mut HitEffects>,
 ) {
     for &event in collision_events.read() {
         let CollisionEvent::Started(entity1, entity2, _) = event else {
@@ -169,12 +256,17 @@ fn detect_hit_events(
         };
 
         let mut handle_collision = |hitbox: Entity, target: Entity| {
-            if !hit_query.contains(hitbox) {
+            let Ok(mut hit) = hit_query.get_mut(hitbox) else {
+                return;
+            };
+            if hit.targets.contains(&target) {
                 return;
             }
+            hit.targets.push(target);
             hit_events.send(HitEvent {
                 hitbox,
                 hurtbox: target,
+                source: hit.source,
             });
         };
 
@@ -187,34 +279,52 @@ fn detect_hit_events(
 pub struct HitEffects {
     pub damage: f32,
     pub knockback: Vec2,
+    /// Applied to targets with `StatusEffects`
+    pub status: Vec<StatusEffect>,
     pub success: bool,
     pub success_sound: Option<Handle<AudioSource>>,
     pub failure_sound: Option<Handle<AudioSource>>,
+    /// The entities that have already been hit
+    pub targets: Vec<Entity>,
+    /// The actor that attacked (level hazards have none)
+    pub source: Option<Entity>,
 }
 
 fn apply_hit_effects(
     mut hit_events: EventReader<HitEvent>,
     mut death_events: EventWriter<DeathEvent>,
-    mut hitbox_query: Query<&mut HitEffects>,
+    mut hitbox_query: Query<(&mut HitEffects, &Transform, Has<Hitbox>)>,
     mut hurtbox_query: Query<(
         Option<&mut Health>,
         Option<&mut Velocity>,
+        Option<&mut StatusEffects>,
         Option<&Children>,
     )>,
     mut body_query: Query<&mut FlinchAnimation>,
-    audio: Res<Audio>,
+    audio: Res<AudioChannel<SfxChannel>>,
+    mut noise_events: EventWriter<NoiseEvent>,
 ) {
-    for &HitEvent { hitbox, hurtbox } in hit_events.read() {
-        let Ok(mut hit) = hitbox_query.get_mut(hitbox) else {
+    for &HitEvent {
+        hitbox, hurtbox, ..
+    } in hit_events.read()
+    {
+        let Ok((mut hit, transform, is_melee)) = hitbox_query.get_mut(hitbox) else {
             continue;
         };
 
-        if let Some(sound) = &hit.success_sound {
-            audio.play(sound.clone()).with_volume(0.4);
+        // Melee hitboxes play their sound when the active window ends
+        if !is_melee {
+            if let Some(sound) = &hit.success_sound {
+                audio.play(sound.clone()).with_volume(SfxChannel::HIT);
+            }
         }
+        noise_events.send(NoiseEvent {
+            position: transform.translation.xy(),
+            loudness: 150.0,
+        });
         hit.success = true;
 
-        let Ok((health, velocity, children)) = hurtbox_query.get_mut(hurtbox) else {
+        let Ok((health, velocity, status, children)) = hurtbox_query.get_mut(hurtbox) else {
             continue;
         };
 
@@ -232,6 +342,13 @@ fn apply_hit_effects(
             velocity.linvel = hit.knockback * scale;
         }
 
+        // Status effects
+        if let Some(mut status) = status {
+            for &effect in &hit.status {
+                status.apply(effect);
+            }
+        }
+
         // Flinch
         for &child in children.into_iter().flatten() {
             if let Ok(mut flinch) = body_query.get_mut(child) {
@@ -241,16 +358,43 @@ fn apply_hit_effects(
     }
 }
 
-fn clean_up_hitboxes(
+// The active window ends early if the animation restarts or its body is gone
+fn update_hitboxes(
+    mut commands: Commands,
     mut despawn: ResMut<DespawnSet>,
-    hitbox_query: Query<(Entity, &HitEffects)>,
-    audio: Res<Audio>,
+    mut hitbox_query: Query<(Entity, &mut Hitbox, &HitEffects, &mut Transform)>,
+    animation_query: Query<&AttackAnimation>,
+    owner_query: Query<&GlobalTransform>,
+    audio: Res<AudioChannel<SfxChannel>>,
+    mut noise_events: EventWriter<NoiseEvent>,
 ) {
-    for (entity, effects) in &hitbox_query {
-        if !effects.success {
-            if let Some(sound) = &effects.failure_sound {
-                audio.play(sound.clone()).with_volume(0.4);
+    for (entity, mut hitbox, effects, mut transform) in &mut hitbox_query {
+        let t = animation_query.get(hitbox.body).map_or(1.0, |anim| anim.t);
+        let restarted = t < hitbox.t;
+        hitbox.t = t;
+
+        if !restarted && t < hitbox.recovery {
+            if t >= hitbox.startup {
+                let progress = (t - hitbox.startup) / (hitbox.recovery - hitbox.startup);
+                if let Ok(gt) = owner_query.get(hitbox.owner) {
+                    transform.translation = hitbox.position(gt.translation(), progress);
+                }
+                commands.entity(entity).remove::<ColliderDisabled>();
             }
+            continue;
+        }
+
+        let sound = if effects.success {
+            &effects.success_sound
+        } else {
+            noise_events.send(NoiseEvent {
+                position: transform.translation.xy(),
+                loudness: 100.0,
+            });
+            &effects.failure_sound
+        };
+        if let Some(sound) = sound {
+            audio.play(sound.clone()).with_volume(SfxChannel::HIT);
         }
 
         despawn.recursive(entity);
@@ -267,7 +411,7 @@ fn apply_hurt_effects(
     mut hit_events: EventReader<HitEvent>,
     hurt_effects_query: Query<&HurtEffects>,
     mut alarm: ResMut<Alarm>,
-    audio: Res<Audio>,
+    audio: Res<AudioChannel<SfxChannel>>,
 ) {
     for &HitEvent { hurtbox, .. } in hit_events.read() {
         let Ok(hurt) = hurt_effects_query.get(hurtbox) else {
@@ -279,7 +423,7 @@ fn apply_hurt_effects(
 
         // Play sound
         if let Some(sound) = &hurt.sound {
-            audio.play(sound.clone()).with_volume(0.4);
+            audio.play(sound.clone()).with_volume(SfxChannel::HIT);
         }
     }
 }
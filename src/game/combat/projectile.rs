@@ -0,0 +1,281 @@
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use crate::common::audio::SfxChannel;
+use crate::common::UpdateSet;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::actor::Actor;
+use crate::game::combat::status::StatusEffect;
+use crate::game::combat::status::StatusKind;
+use crate::game::combat::CombatAssets;
+use crate::game::combat::HitEffects;
+use crate::game::combat::HitEvent;
+use crate::game::inventory::Inventory;
+use crate::game::level::solid_filter;
+use crate::game::level::Solids;
+use crate::game::noise::NoiseEvent;
+use crate::util::y_sort::YSort;
+use crate::util::DespawnSet;
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<RangedAttack>().add_systems(
+            FixedUpdate,
+            spawn_projectiles.in_set(UpdateSet::ApplyIntents),
+        );
+
+        app.register_type::<Projectile>().add_systems(
+            FixedUpdate,
+            (
+                move_projectiles.in_set(UpdateSet::Update),
+                apply_projectile_hits.in_set(UpdateSet::HandleEvents),
+            ),
+        );
+    }
+}
+
+/// Lets an actor act on `ActorIntent::shoot` (defaults to a thrown rock that stuns)
+#[derive(Component, Reflect, Deserialize, Clone)]
+#[serde(default)]
+pub struct RangedAttack {
+    pub speed: f32,
+    pub radius: f32,
+    pub damage: f32,
+    pub knockback: f32,
+    pub lifetime: f32,
+    pub pierce: u32,
+    pub bounce: u32,
+    pub status: Vec<StatusEffect>,
+    pub cooldown: f32,
+    #[serde(skip)]
+    pub cooldown_t: f32,
+}
+
+impl Default for RangedAttack {
+    fn default() -> Self {
+        Self {
+            speed: 250.0,
+            radius: 3.0,
+            damage: 4.0,
+            knockback: 4.0,
+            lifetime: 0.8,
+            pierce: 0,
+            bounce: 0,
+            status: vec![StatusEffect {
+                kind: StatusKind::Stun,
+                duration: 1.0,
+            }],
+            cooldown: 1.0,
+            cooldown_t: 0.0,
+        }
+    }
+}
+
+fn spawn_projectiles(
+    mut commands: Commands,
+    combat_assets: Res<CombatAssets>,
+    mut actor_query: Query<(
+        Entity,
+        &Actor,
+        &mut RangedAttack,
+        &GlobalTransform,
+        &ActorIntent,
+        Option<&mut Inventory>,
+    )>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, actor, mut ranged, gt, intent, inventory) in &mut actor_query {
+        ranged.cooldown_t -= dt;
+        let Some(direction) = intent.shoot else {
+            continue;
+        };
+        if ranged.cooldown_t > 0.0 {
+            continue;
+        }
+        // Actors with an inventory need a rock to throw
+        if inventory.is_some_and(|mut inventory| !inventory.take_rock()) {
+            continue;
+        }
+        ranged.cooldown_t = ranged.cooldown;
+
+        ProjectileTemplate {
+            position: gt.translation(),
+            direction,
+            speed: ranged.speed,
+            radius: ranged.radius,
+            damage: ranged.damage,
+            knockback: ranged.knockback,
+            lifetime: ranged.lifetime,
+            pierce: ranged.pierce,
+            bounce: ranged.bounce,
+            status: ranged.status.clone(),
+            groups: actor.faction.hitbox_groups(),
+            source: Some(entity),
+        }
+        .spawn(&mut commands, &combat_assets);
+    }
+}
+
+pub struct ProjectileTemplate {
+    pub position: Vec3,
+    pub direction: Vec2,
+    pub speed: f32,
+    pub radius: f32,
+    pub damage: f32,
+    pub knockback: f32,
+    pub lifetime: f32,
+    pub pierce: u32,
+    pub bounce: u32,
+    pub status: Vec<StatusEffect>,
+    pub groups: CollisionGroups,
+    /// The actor that fired the projectile
+    pub source: Option<Entity>,
+}
+
+impl ProjectileTemplate {
+    pub fn spawn(self, commands: &mut Commands, combat_assets: &CombatAssets) -> Entity {
+        let sprite = commands
+            .spawn((
+                Name::new("Sprite"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgb(0.45, 0.4, 0.35),
+                        custom_size: Some(Vec2::splat(2.0 * self.radius)),
+                        ..default()
+                    },
+                    // Fly at about hand height
+                    transform: Transform::from_xyz(0.0, 10.0, 0.0),
+                    ..default()
+                },
+            ))
+            .id();
+
+        commands
+            .spawn((
+                Name::new("Projectile"),
+                SpatialBundle {
+                    transform: Transform::from_translation(self.position),
+                    ..default()
+                },
+                YSort,
+                RigidBody::KinematicVelocityBased,
+                Velocity::linear(self.speed * self.direction),
+                Collider::ball(self.radius),
+                Sensor,
+                self.groups,
+                ActiveEvents::COLLISION_EVENTS,
+                HitEffects {
+                    damage: self.damage,
+                    knockback: self.knockback * self.direction,
+                    status: self.status,
+                    success_sound: Some(combat_assets.attack_hit.clone()),
+                    failure_sound: Some(combat_assets.attack_miss.clone()),
+                    source: self.source,
+                    ..default()
+                },
+                Projectile {
+                    lifetime: self.lifetime,
+                    pierce: self.pierce,
+                    bounce: self.bounce,
+                },
+            ))
+            .add_child(sprite)
+            .id()
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct Projectile {
+    /// Seconds until the projectile falls to the ground
+    pub lifetime: f32,
+    /// How many more targets the projectile can pass through
+    pub pierce: u32,
+    /// How many more times the projectile can bounce off walls
+    pub bounce: u32,
+}
+
+// Projectiles stop on walls and closed gates unless they can bounce
+fn move_projectiles(
+    mut despawn: ResMut<DespawnSet>,
+    mut projectile_query: Query<(
+        Entity,
+        &mut Projectile,
+        &mut Velocity,
+        &mut HitEffects,
+        &Transform,
+    )>,
+    rapier_context: Res<RapierContext>,
+    solids: Solids,
+    audio: Res<AudioChannel<SfxChannel>>,
+    mut noise_events: EventWriter<NoiseEvent>,
+    time: Res<Time>,
+) {
+    let is_solid = |entity| solids.contains(entity);
+    let filter = solid_filter(&is_solid);
+
+    let dt = time.delta_seconds();
+    for (entity, mut projectile, mut velocity, mut hit, transform) in &mut projectile_query {
+        let position = transform.translation.xy();
+        projectile.lifetime -= dt;
+        let mut stopped = projectile.lifetime <= 0.0;
+
+        // Look ahead by one step
+        let speed = velocity.linvel.length();
+        if !stopped && speed > 0.0 {
+            let direction = velocity.linvel / speed;
+            if let Some((_, intersection)) = rapier_context.cast_ray_and_get_normal(
+                position,
+                direction,
+                speed * dt,
+                true,
+                filter,
+            ) {
+                if projectile.bounce > 0 {
+                    projectile.bounce -= 1;
+                    let normal = intersection.normal;
+                    velocity.linvel -= 2.0 * velocity.linvel.dot(normal) * normal;
+                    hit.knockback -= 2.0 * hit.knockback.dot(normal) * normal;
+                } else {
+                    stopped = true;
+                }
+            }
+        }
+        if !stopped {
+            continue;
+        }
+
+        if !hit.success {
+            if let Some(sound) = &hit.failure_sound {
+                audio.play(sound.clone()).with_volume(SfxChannel::HIT);
+            }
+        }
+        noise_events.send(NoiseEvent {
+            position,
+            loudness: 100.0,
+        });
+        despawn.recursive(entity);
+    }
+}
+
+fn apply_projectile_hits(
+    mut despawn: ResMut<DespawnSet>,
+    mut hit_events: EventReader<HitEvent>,
+    mut projectile_query: Query<&mut Projectile>,
+) {
+    for &HitEvent { hitbox, .. } in hit_events.read() {
+        let Ok(mut projectile) = projectile_query.get_mut(hitbox) else {
+            continue;
+        };
+
+        if projectile.pierce > 0 {
+            projectile.pierce -= 1;
+        } else {
+            despawn.recursive(hitbox);
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use std::f32::consts::TAU;
+use std::mem::discriminant;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::common::PostTransformSet;
+use crate::common::UpdateSet;
+use crate::game::actor::body::Body;
+use crate::game::actor::health::Health;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::combat::DeathEvent;
+
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<StatusEffects>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    tick_status_effects.in_set(UpdateSet::Start),
+                    block_intents.in_set(UpdateSet::BlockIntents),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                apply_knockdown_pose.in_set(PostTransformSet::Blend),
+            );
+
+        app.register_type::<StatusIcon>()
+            .add_systems(Update, update_status_icons.in_set(UpdateSet::UpdateUi));
+    }
+}
+
+#[derive(Reflect, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum StatusKind {
+    /// Blocks intents
+    Stun,
+    /// Deals damage per second
+    Bleed(f32),
+    /// Multiplies speed
+    Slow(f32),
+    /// Blocks intents and knocks the body over
+    Knockdown,
+}
+
+impl StatusKind {
+    const ICON_COLORS: [Color; 4] = [
+        Color::rgba(0.9, 0.8, 0.2, 0.8),
+        Color::rgba(0.8, 0.1, 0.15, 0.8),
+        Color::rgba(0.3, 0.6, 0.9, 0.8),
+        Color::rgba(0.6, 0.4, 0.8, 0.8),
+    ];
+
+    fn icon_index(&self) -> usize {
+        match self {
+            Self::Stun => 0,
+            Self::Bleed(_) => 1,
+            Self::Slow(_) => 2,
+            Self::Knockdown => 3,
+        }
+    }
+}
+
+#[derive(Reflect, Deserialize, Copy, Clone, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    /// Seconds remaining
+    pub duration: f32,
+}
+
+/// The status effects currently applied to an actor
+#[derive(Component, Reflect, Default)]
+pub struct StatusEffects(pub Vec<StatusEffect>);
+
+impl StatusEffects {
+    const MAX_BLEED_STACKS: usize = 5;
+
+    /// Bleed stacks, while other effects refresh an existing effect of the same kind
+    pub fn apply(&mut self, effect: StatusEffect) {
+        if let StatusKind::Bleed(_) = effect.kind {
+            let stacks = self
+                .0
+                .iter()
+                .filter(|other| matches!(other.kind, StatusKind::Bleed(_)))
+                .count();
+            if stacks < Self::MAX_BLEED_STACKS {
+                self.0.push(effect);
+            } else if let Some(oldest) = self
+                .0
+                .iter_mut()
+                .filter(|other| matches!(other.kind, StatusKind::Bleed(_)))
+                .min_by(|a, b| a.duration.total_cmp(&b.duration))
+            {
+                // Replace the stack closest to running out
+                *oldest = effect;
+            }
+            return;
+        }
+
+        let Some(old) = self
+            .0
+            .iter_mut()
+            .find(|other| discriminant(&other.kind) == discriminant(&effect.kind))
+        else {
+            self.0.push(effect);
+            return;
+        };
+        old.duration = old.duration.max(effect.duration);
+        // Keep the strongest slow
+        if let (StatusKind::Slow(old_scale), StatusKind::Slow(scale)) = (&mut old.kind, effect.kind)
+        {
+            *old_scale = old_scale.min(scale);
+        }
+    }
+
+    pub fn is_knocked_down(&self) -> bool {
+        self.0
+            .iter()
+            .any(|effect| effect.kind == StatusKind::Knockdown)
+    }
+
+    pub fn blocks_intents(&self) -> bool {
+        self.0
+            .iter()
+            .any(|effect| matches!(effect.kind, StatusKind::Stun | StatusKind::Knockdown))
+    }
+
+    pub fn speed_scale(&self) -> f32 {
+        self.0
+            .iter()
+            .filter_map(|effect| match effect.kind {
+                StatusKind::Slow(scale) => Some(scale),
+                _ => None,
+            })
+            .product()
+    }
+
+    fn bleed_per_second(&self) -> f32 {
+        self.0
+            .iter()
+            .filter_map(|effect| match effect.kind {
+                StatusKind::Bleed(damage) => Some(damage),
+                _ => None,
+            })
+            .sum()
+    }
+}
+
+fn tick_status_effects(
+    mut actor_query: Query<(Entity, &mut StatusEffects, &mut Health)>,
+    mut death_events: EventWriter<DeathEvent>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, mut status, mut health) in &mut actor_query {
+        if status.0.is_empty() {
+            continue;
+        }
+        // Corpses shrug off their status effects
+        if health.current <= 0.0 {
+            status.0.clear();
+            continue;
+        }
+
+        let bleed = status.bleed_per_second() * dt;
+        if bleed > 0.0 {
+            if health.current <= bleed {
+                death_events.send(DeathEvent(entity));
+            }
+            health.current -= bleed;
+        }
+
+        for effect in &mut status.0 {
+            effect.duration -= dt;
+        }
+        status.0.retain(|effect| effect.duration > 0.0);
+    }
+}
+
+fn block_intents(mut actor_query: Query<(&StatusEffects, &mut ActorIntent)>) {
+    for (status, mut intent) in &mut actor_query {
+        if status.blocks_intents() {
+            *intent = default();
+        }
+    }
+}
+
+fn apply_knockdown_pose(
+    mut body_query: Query<(&Parent, &mut Transform), With<Body>>,
+    status_query: Query<&StatusEffects>,
+) {
+    for (parent, mut transform) in &mut body_query {
+        let Ok(status) = status_query.get(parent.get()) else {
+            continue;
+        };
+        if !status.is_knocked_down() {
+            continue;
+        }
+
+        transform.translation.y -= 8.0;
+        transform.rotation *= Quat::from_rotation_z(TAU / 4.0);
+    }
+}
+
+/// Shows while its actor has a status effect of the corresponding kind
+#[derive(Component, Reflect)]
+pub struct StatusIcon(usize);
+
+impl StatusIcon {
+    const SIZE: f32 = 3.0;
+    const SPACING: f32 = 1.0;
+}
+
+fn update_status_icons(
+    mut icon_query: Query<(&StatusIcon, &Parent, &mut Visibility, &mut Transform)>,
+    parent_query: Query<&Parent, Without<StatusIcon>>,
+    status_query: Query<&StatusEffects>,
+) {
+    for (icon, parent, mut visibility, mut transform) in &mut icon_query {
+        let Ok(parent) = parent_query.get(parent.get()) else {
+            continue;
+        };
+        let Ok(status) = status_query.get(parent.get()) else {
+            continue;
+        };
+
+        let mut shown = [false; StatusKind::ICON_COLORS.len()];
+        for effect in &status.0 {
+            shown[effect.kind.icon_index()] = true;
+        }
+
+        *visibility = if shown[icon.0] {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        // Pack the shown icons together from the left
+        let slot = shown[..icon.0].iter().filter(|&&x| x).count();
+        transform.translation.x = slot as f32 * (StatusIcon::SIZE + StatusIcon::SPACING);
+    }
+}
+
+/// A row of status icons, meant to sit beside the health bar
+pub struct StatusIconsTemplate {
+    pub offset: Transform,
+}
+
+impl StatusIconsTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        // Children
+        let icons = StatusKind::ICON_COLORS
+            .iter()
+            .enumerate()
+            .map(|(i, &color)| {
+                commands
+                    .spawn((
+                        Name::new("StatusIcon"),
+                        SpriteBundle {
+                            sprite: Sprite {
+                                color,
+                                custom_size: Some(Vec2::splat(StatusIcon::SIZE)),
+                                ..default()
+                            },
+                            transform: Transform::from_xyz(0.0, 0.0, 0.001),
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                        StatusIcon(i),
+                    ))
+                    .id()
+            })
+            .collect::<Vec<_>>();
+
+        // Parent
+        commands
+            .spawn((
+                Name::new("StatusIcons"),
+                SpatialBundle {
+                    transform: self.offset,
+                    ..default()
+                },
+            ))
+            .push_children(&icons)
+            .id()
+    }
+}
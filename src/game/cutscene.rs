@@ -3,7 +3,11 @@ use bevy::ui::Val::*;
 use bevy_asset_loader::prelude::*;
 use bevy_kira_audio::prelude::*;
 use leafwing_input_manager::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
 
+use crate::common::audio::UiChannel;
+use crate::common::storage::SaveStorage;
 use crate::common::UpdateSet;
 use crate::game::actor::health::Health;
 use crate::game::actor::intent::ActorIntent;
@@ -11,6 +15,9 @@ use crate::game::actor::player::PlayerControl;
 use crate::game::actor::player::Playthrough;
 use crate::game::alarm::Alarm;
 use crate::game::level::victory::Victory;
+use crate::game::score::RunHistory;
+use crate::game::score::RunRecord;
+use crate::game::score::Score;
 use crate::util::ui::font::PIXEL_FONT_HANDLE;
 use crate::util::ui::UiRoot;
 use crate::util::DespawnSet;
@@ -22,13 +29,9 @@ impl Plugin for CutscenePlugin {
         app.register_type::<CutsceneAssets>()
             .init_collection::<CutsceneAssets>();
 
-        app.register_type::<Cutscene>().add_systems(
-            Update,
-            (
-                update_cutscene.in_set(UpdateSet::Update),
-                advance_cutscenes.in_set(UpdateSet::HandleActions),
-            ),
-        );
+        app.register_type::<Cutscene>()
+            .add_systems(FixedUpdate, update_cutscene.in_set(UpdateSet::Update))
+            .add_systems(Update, advance_cutscenes.in_set(UpdateSet::HandleActions));
 
         app.add_plugins(InputManagerPlugin::<CutsceneAction>::default());
 
@@ -78,7 +81,7 @@ fn update_cutscene(
     }
 }
 
-#[derive(Actionlike, Reflect, Clone, Hash, PartialEq, Eq)]
+#[derive(Actionlike, Reflect, Serialize, Deserialize, Clone, Hash, PartialEq, Eq, Debug)]
 pub enum CutsceneAction {
     Advance,
 }
@@ -91,7 +94,7 @@ fn advance_cutscenes(
         &mut Text,
         &mut Cutscene,
     )>,
-    audio: Res<Audio>,
+    audio: Res<AudioChannel<UiChannel>>,
 ) {
     for (entity, action, mut text, mut cutscene) in &mut cutscene_query {
         if !action.just_pressed(&CutsceneAction::Advance) {
@@ -161,13 +164,8 @@ impl CutsceneTemplate {
                         cutscene_assets.sfx_confirm3.clone(),
                     ],
                 },
-                InputManagerBundle::with_map(
-                    InputMap::default()
-                        .insert(CutsceneAction::Advance, KeyCode::Space)
-                        .insert(CutsceneAction::Advance, KeyCode::Enter)
-                        .insert(CutsceneAction::Advance, MouseButton::Left)
-                        .build(),
-                ),
+                // The InputMap is filled in from Keybindings
+                InputManagerBundle::<CutsceneAction>::default(),
             ))
             .id()
     }
@@ -219,6 +217,10 @@ impl MessageTemplate {
 fn show_death_message(
     mut commands: Commands,
     ui_root: Res<UiRoot>,
+    playthrough: Res<Playthrough>,
+    time: Res<Time<Fixed>>,
+    mut history: ResMut<RunHistory>,
+    mut storage: ResMut<SaveStorage>,
     message_query: Query<(), With<Message>>,
     player_query: Query<(), (With<PlayerControl>, Without<ActorIntent>)>,
 ) {
@@ -226,6 +228,11 @@ fn show_death_message(
         return;
     }
 
+    history.add(
+        RunRecord::now(false, default(), &playthrough, time.elapsed_seconds()),
+        &mut storage,
+    );
+
     let message = MessageTemplate {
         title: "You died.".to_string(),
         body: "(press R to restart)".to_string(),
@@ -240,7 +247,9 @@ fn show_victory_message(
     playthrough: Res<Playthrough>,
     victory: Res<Victory>,
     alarm: Res<Alarm>,
-    time: Res<Time>,
+    time: Res<Time<Fixed>>,
+    mut history: ResMut<RunHistory>,
+    mut storage: ResMut<SaveStorage>,
     message_query: Query<(), With<Message>>,
     health_query: Query<&Health, With<PlayerControl>>,
 ) {
@@ -251,23 +260,20 @@ fn show_victory_message(
         return;
     };
 
-    let alarm_scale = 100_000.0;
-    let alarm_t = 1.0 - alarm.0;
-    let alarm_score = (alarm_scale * alarm_t).round() as i32;
-
-    let health_scale = 10_000.0;
-    let health_t = health.current / health.max;
-    let health_score = (health_scale * health_t).round() as i32;
-
-    let time_scale = 50_000.0 * 60.0;
-    let time_t = time.elapsed_seconds() - playthrough.start_time;
-    let time_score = (time_scale / time_t).round() as i32;
+    let now = time.elapsed_seconds();
+    let score = Score::new(alarm.0, health, now - playthrough.start_time);
+    history.add(RunRecord::now(true, score, &playthrough, now), &mut storage);
 
-    let score = alarm_score + health_score + time_score;
+    let Score {
+        alarm: alarm_score,
+        health: health_score,
+        time: time_score,
+    } = score;
+    let score = score.total();
 
     let message = MessageTemplate {
             title: "You escaped!".to_string(),
-            body: format!("Alarm score: {alarm_score}\n\n\n\n\nHealth score: {health_score}\n\n\n\n\nTime score: {time_score}\n\n\n\n\nTotal score: {score}\n\n\n\n\n(press R to play again)"),
+            body: format!("Alarm score: {alarm_score}\n\n\n\n\nHealth score: {health_score}\n\n\n\n\nTime score: {time_score}\n\n\n\n\nTotal score: {score}\n\n\n\n\n(press R to play again)\n\n\n\n\n(press H for high scores)"),
         }
         .spawn(&mut commands);
     commands.entity(message).set_parent(ui_root.body);
@@ -0,0 +1,169 @@
+use bevy::math::vec2;
+use bevy::prelude::*;
+
+use crate::common::UpdateSet;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::actor::player::PlayerControl;
+use crate::util::ui::backdrop::BackdropTemplate;
+use crate::util::ui::font::BOLD_FONT_HANDLE;
+use crate::util::y_sort::Z_MAX;
+
+pub struct InteractPlugin;
+
+impl Plugin for InteractPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Interactable>()
+            .add_event::<InteractEvent>()
+            .add_systems(
+                FixedUpdate,
+                apply_interact_intents.in_set(UpdateSet::ApplyIntents),
+            );
+
+        app.register_type::<InteractPrompt>()
+            .register_type::<InteractPromptText>()
+            .add_systems(Update, update_interact_prompt.in_set(UpdateSet::UpdateUi));
+    }
+}
+
+/// Something an actor can act on with `ActorIntent::interact` from within `radius`
+#[derive(Component, Reflect)]
+pub struct Interactable {
+    pub radius: f32,
+    /// Shown above the interactable when the player is in range
+    pub prompt: String,
+}
+
+impl Interactable {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            radius: 20.0,
+            prompt: prompt.into(),
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct InteractEvent {
+    pub actor: Entity,
+    pub target: Entity,
+}
+
+/// The closest interactable in range of a position
+fn closest_interactable<'a>(
+    position: Vec2,
+    interactables: impl Iterator<Item = (Entity, &'a Interactable, &'a GlobalTransform)>,
+) -> Option<Entity> {
+    interactables
+        .map(|(entity, interactable, gt)| {
+            let distance = gt.translation().xy().distance(position);
+            (entity, distance - interactable.radius)
+        })
+        .filter(|&(_, overlap)| overlap <= 0.0)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
+}
+
+fn apply_interact_intents(
+    actor_query: Query<(Entity, &ActorIntent, &GlobalTransform)>,
+    interactable_query: Query<(Entity, &Interactable, &GlobalTransform)>,
+    mut interact_events: EventWriter<InteractEvent>,
+) {
+    for (actor, intent, gt) in &actor_query {
+        if !intent.interact {
+            continue;
+        }
+        let Some(target) = closest_interactable(gt.translation().xy(), interactable_query.iter())
+        else {
+            continue;
+        };
+
+        interact_events.send(InteractEvent { actor, target });
+    }
+}
+
+/// Shows the prompt of the interactable closest to the player
+#[derive(Component, Reflect)]
+pub struct InteractPrompt;
+
+impl InteractPrompt {
+    const OFFSET: Vec2 = Vec2::new(0.0, 24.0);
+}
+
+#[derive(Component, Reflect)]
+pub struct InteractPromptText;
+
+fn update_interact_prompt(
+    mut prompt_query: Query<(&mut Visibility, &mut Transform), With<InteractPrompt>>,
+    mut text_query: Query<&mut Text, With<InteractPromptText>>,
+    player_query: Query<&GlobalTransform, With<PlayerControl>>,
+    interactable_query: Query<(Entity, &Interactable, &GlobalTransform)>,
+) {
+    let Ok((mut visibility, mut transform)) = prompt_query.get_single_mut() else {
+        return;
+    };
+    let Some((_, interactable, target_gt)) = player_query
+        .get_single()
+        .ok()
+        .and_then(|gt| closest_interactable(gt.translation().xy(), interactable_query.iter()))
+        .and_then(|target| interactable_query.get(target).ok())
+    else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    *visibility = Visibility::Inherited;
+    // Draw above everything that is y-sorted
+    transform.translation = (target_gt.translation().xy() + InteractPrompt::OFFSET).extend(Z_MAX);
+    for mut text in &mut text_query {
+        if text.sections[0].value != interactable.prompt {
+            text.sections[0].value.clone_from(&interactable.prompt);
+        }
+    }
+}
+
+pub struct InteractPromptTemplate;
+
+impl InteractPromptTemplate {
+    const TEXT_COLOR: Color = Color::rgba(0.95, 0.9, 0.6, 0.9);
+
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        let style = TextStyle {
+            font: BOLD_FONT_HANDLE,
+            font_size: 16.0,
+            color: Self::TEXT_COLOR,
+        };
+
+        // Children
+        let text = commands
+            .spawn((
+                Name::new("Text"),
+                Text2dBundle {
+                    text: Text::from_section("", style),
+                    transform: Transform::from_xyz(0.0, 0.0, 0.001)
+                        .with_scale(Vec2::splat(0.25).extend(1.0)),
+                    ..default()
+                },
+                InteractPromptText,
+            ))
+            .id();
+        let backdrop = BackdropTemplate {
+            size: vec2(40.0, 4.0),
+            offset: default(),
+        }
+        .spawn(commands);
+        commands.entity(backdrop).add_child(text);
+
+        // Parent
+        commands
+            .spawn((
+                Name::new("InteractPrompt"),
+                SpatialBundle {
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                InteractPrompt,
+            ))
+            .add_child(backdrop)
+            .id()
+    }
+}
@@ -0,0 +1,322 @@
+use bevy::prelude::*;
+use bevy::ui::Val::*;
+use bevy_asset_loader::prelude::*;
+use bevy_kira_audio::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::common::audio::SfxChannel;
+use crate::common::UpdateSet;
+use crate::game::actor::health::Health;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::actor::player::PlayerControl;
+use crate::game::combat::COLLISION_GROUP;
+use crate::util::ui::backdrop::BackdropTemplate;
+use crate::util::ui::font::FontSize;
+use crate::util::ui::font::BOLD_FONT_HANDLE;
+use crate::util::y_sort::YSort;
+use crate::util::DespawnSet;
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<InventoryAssets>()
+            .init_collection::<InventoryAssets>();
+
+        app.register_type::<Inventory>()
+            .add_systems(FixedUpdate, drink_potions.in_set(UpdateSet::ApplyIntents));
+
+        app.register_type::<Pickup>()
+            .add_systems(FixedUpdate, collect_pickups.in_set(UpdateSet::Start));
+
+        app.register_type::<InventoryCount>()
+            .add_systems(Update, update_inventory_hud.in_set(UpdateSet::UpdateUi));
+    }
+}
+
+#[derive(AssetCollection, Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct InventoryAssets {
+    #[asset(path = "sound/sfx/pop_1.wav")]
+    pickup: Handle<AudioSource>,
+    #[asset(path = "sound/sfx/pop_2.wav")]
+    drink: Handle<AudioSource>,
+}
+
+#[derive(Reflect, Clone, PartialEq, Debug)]
+pub enum Item {
+    /// Restores health when drunk
+    Potion,
+    /// Ammo for `ActorIntent::shoot`
+    Rock,
+    /// Opens the locked doors with the same name
+    Key(String),
+}
+
+impl Item {
+    const COLORS: [Color; 3] = [
+        Color::rgb(0.85, 0.2, 0.3),
+        Color::rgb(0.45, 0.4, 0.35),
+        Color::rgb(0.9, 0.75, 0.3),
+    ];
+
+    fn index(&self) -> usize {
+        match self {
+            Self::Potion => 0,
+            Self::Rock => 1,
+            Self::Key(_) => 2,
+        }
+    }
+
+    /// Parse the LDtk `item` and `key` fields
+    pub fn from_name(name: &str, key: Option<&str>) -> Option<Self> {
+        Some(match name {
+            "potion" => Self::Potion,
+            "rock" => Self::Rock,
+            "key" => Self::Key(key.unwrap_or("bronze").to_string()),
+            _ => return None,
+        })
+    }
+}
+
+/// The items an actor is carrying (carries over between levels for the player)
+#[derive(Component, Reflect, Clone, Default)]
+pub struct Inventory {
+    pub potions: u32,
+    pub rocks: u32,
+    pub keys: Vec<String>,
+}
+
+impl Inventory {
+    const POTION_HEAL: f32 = 60.0;
+
+    /// The player's inventory at the start of a run
+    pub fn starting() -> Self {
+        Self {
+            rocks: 5,
+            ..default()
+        }
+    }
+
+    pub fn add(&mut self, item: Item, count: u32) {
+        match item {
+            Item::Potion => self.potions += count,
+            Item::Rock => self.rocks += count,
+            Item::Key(key) => self.keys.push(key),
+        }
+    }
+
+    pub fn has_key(&self, key: &str) -> bool {
+        self.keys.iter().any(|x| x == key)
+    }
+
+    /// Take a rock to throw, if there is one
+    pub fn take_rock(&mut self) -> bool {
+        if self.rocks == 0 {
+            return false;
+        }
+        self.rocks -= 1;
+        true
+    }
+
+    fn counts(&self) -> [usize; 3] {
+        [self.potions as usize, self.rocks as usize, self.keys.len()]
+    }
+}
+
+fn drink_potions(
+    mut actor_query: Query<(&ActorIntent, &mut Inventory, &mut Health)>,
+    inventory_assets: Res<InventoryAssets>,
+    audio: Res<AudioChannel<SfxChannel>>,
+) {
+    for (intent, mut inventory, mut health) in &mut actor_query {
+        if !intent.drink || inventory.potions == 0 || health.current >= health.max {
+            continue;
+        }
+
+        inventory.potions -= 1;
+        health.current = (health.current + Inventory::POTION_HEAL).min(health.max);
+        audio
+            .play(inventory_assets.drink.clone())
+            .with_volume(SfxChannel::ITEM);
+    }
+}
+
+/// An item lying in the level that the player collects by walking over it
+#[derive(Component, Reflect)]
+pub struct Pickup {
+    pub item: Item,
+    pub count: u32,
+}
+
+fn collect_pickups(
+    mut despawn: ResMut<DespawnSet>,
+    mut collision_events: EventReader<CollisionEvent>,
+    pickup_query: Query<&Pickup>,
+    mut inventory_query: Query<&mut Inventory>,
+    inventory_assets: Res<InventoryAssets>,
+    audio: Res<AudioChannel<SfxChannel>>,
+) {
+    for &event in collision_events.read() {
+        let CollisionEvent::Started(entity1, entity2, _) = event else {
+            continue;
+        };
+
+        for (pickup_entity, collector) in [(entity1, entity2), (entity2, entity1)] {
+            let Ok(pickup) = pickup_query.get(pickup_entity) else {
+                continue;
+            };
+            let Ok(mut inventory) = inventory_query.get_mut(collector) else {
+                continue;
+            };
+
+            inventory.add(pickup.item.clone(), pickup.count);
+            despawn.recursive(pickup_entity);
+            audio
+                .play(inventory_assets.pickup.clone())
+                .with_volume(SfxChannel::ITEM);
+        }
+    }
+}
+
+pub struct PickupTemplate {
+    pub transform: Transform,
+    pub item: Item,
+    pub count: u32,
+}
+
+impl PickupTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("Pickup"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Item::COLORS[self.item.index()],
+                        custom_size: Some(Vec2::splat(5.0)),
+                        ..default()
+                    },
+                    transform: self.transform,
+                    ..default()
+                },
+                YSort,
+                Collider::ball(4.0),
+                // Not the hurtbox groups, which actors drop while dodging
+                CollisionGroups {
+                    memberships: COLLISION_GROUP,
+                    filters: COLLISION_GROUP,
+                },
+                Sensor,
+                ActiveEvents::COLLISION_EVENTS,
+                Pickup {
+                    item: self.item,
+                    count: self.count,
+                },
+            ))
+            .id()
+    }
+}
+
+/// Shows how many items of the corresponding kind the player is carrying
+#[derive(Component, Reflect)]
+pub struct InventoryCount(usize);
+
+fn update_inventory_hud(
+    mut count_query: Query<(&InventoryCount, &mut Text)>,
+    player_query: Query<&Inventory, With<PlayerControl>>,
+) {
+    let Ok(inventory) = player_query.get_single() else {
+        return;
+    };
+
+    let counts = inventory.counts();
+    for (count, mut text) in &mut count_query {
+        let value = counts[count.0].to_string();
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+/// A strip of item counts, meant to sit under the alarm meter
+pub struct InventoryHudTemplate;
+
+impl InventoryHudTemplate {
+    const TEXT_COLOR: Color = Color::rgba(0.9, 0.9, 0.85, 0.8);
+
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        // Children
+        let slots = Item::COLORS
+            .iter()
+            .enumerate()
+            .map(|(i, &color)| {
+                let swatch = commands
+                    .spawn((
+                        Name::new("InventorySwatch"),
+                        NodeBundle {
+                            style: Style {
+                                width: Vw(1.2),
+                                height: Vw(1.2),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(color),
+                            ..default()
+                        },
+                    ))
+                    .id();
+                let count = commands
+                    .spawn((
+                        Name::new("InventoryCount"),
+                        TextBundle::from_section(
+                            "0",
+                            TextStyle {
+                                font: BOLD_FONT_HANDLE,
+                                color: Self::TEXT_COLOR,
+                                ..default()
+                            },
+                        ),
+                        FontSize::new(Vw(1.5)).with_step(8.0),
+                        InventoryCount(i),
+                    ))
+                    .id();
+
+                commands
+                    .spawn((
+                        Name::new("InventorySlot"),
+                        NodeBundle {
+                            style: Style {
+                                align_items: AlignItems::Center,
+                                column_gap: Vw(0.5),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                    ))
+                    .add_child(swatch)
+                    .add_child(count)
+                    .id()
+            })
+            .collect::<Vec<_>>();
+
+        // Parent
+        commands
+            .spawn((
+                Name::new("InventoryHud"),
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Percent(14.0),
+                        left: Percent(1.0),
+                        align_items: AlignItems::Center,
+                        column_gap: Vw(1.5),
+                        padding: UiRect::axes(Vw(0.8), Vw(0.4)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(BackdropTemplate::COLOR),
+                    ..default()
+                },
+            ))
+            .push_children(&slots)
+            .id()
+    }
+}
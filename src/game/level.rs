@@ -1,27 +1,62 @@
+use bevy::ecs::system::SystemParam;
+use bevy::math::vec2;
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 use bevy_asset_loader::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
 
+use crate::common::rng::GameRng;
+use crate::game::actor::archetype::ArchetypeAssets;
+use crate::game::actor::archetype::EnemyArchetype;
+use crate::game::actor::archetype::EnemyArchetypes;
 use crate::game::actor::enemy::EnemyTemplate;
+use crate::game::actor::enemy::PatrolPointTemplate;
 use crate::game::actor::player::PlayerTemplate;
 use crate::game::actor::player::Playthrough;
+use crate::game::actor::search::Searchable;
 use crate::game::actor::ActorAssets;
+use crate::game::combat::COLLISION_GROUP;
+use crate::game::interact::Interactable;
+use crate::game::inventory::Inventory;
+use crate::game::inventory::Item;
+use crate::game::inventory::PickupTemplate;
+use crate::game::level::door::DoorTemplate;
 use crate::game::level::exit::ExitTemplate;
+use crate::game::level::gate::Gate;
 use crate::game::level::gate::GateAssets;
 use crate::game::level::gate::GateTemplate;
+use crate::game::level::hazard::PortcullisTemplate;
+use crate::game::level::hazard::SpikesTemplate;
+use crate::game::level::hazard::TurretTemplate;
+use crate::game::level::lever::LeverTemplate;
 use crate::game::level::plate::PlateAssets;
 use crate::game::level::plate::PlateTemplate;
+use crate::game::level::signal::Logic;
+use crate::game::level::signal::LogicTemplate;
+use crate::game::level::signal::Signal;
+use crate::game::level::signal::SignalTimerTemplate;
+use crate::game::level::spawner::SpawnerTemplate;
 use crate::game::level::victory::VictorySquareTemplate;
+use crate::game::level::wall::Wall;
 use crate::game::level::wall::WallTemplate;
 use crate::util::vfx::VfxAssets;
 use crate::util::DespawnSet;
 
+pub mod door;
 mod exit;
-mod gate;
+pub mod gate;
+pub mod hazard;
+pub mod lever;
+pub mod nav_grid;
 pub mod plate;
+pub mod signal;
+pub mod spawner;
 pub mod victory;
-mod wall;
+pub mod wall;
+
+/// The size of a grid cell in pixels
+const GRID_SIZE: f32 = 16.0;
 
 pub struct LevelPlugin;
 
@@ -44,9 +79,15 @@ impl Plugin for LevelPlugin {
         app.add_systems(PreUpdate, populate_level);
 
         app.add_plugins((
+            door::DoorPlugin,
             exit::ExitPlugin,
             gate::GatePlugin,
+            hazard::HazardPlugin,
+            lever::LeverPlugin,
+            nav_grid::NavGridPlugin,
             plate::PlatePlugin,
+            signal::SignalPlugin,
+            spawner::SpawnerPlugin,
             victory::VictoryPlugin,
             wall::WallPlugin,
         ));
@@ -76,24 +117,163 @@ impl LevelTemplate {
     }
 }
 
+/// Walls and closed gates, which block projectiles and line of sight
+#[derive(SystemParam)]
+pub struct Solids<'w, 's> {
+    wall_query: Query<'w, 's, (), With<Wall>>,
+    gate_query: Query<'w, 's, &'static Gate>,
+}
+
+impl Solids<'_, '_> {
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.wall_query.contains(entity) || self.gate_query.get(entity).is_ok_and(|gate| !gate.open)
+    }
+}
+
+/// A scene query filter that only hits solids, given `|entity| solids.contains(entity)`
+pub fn solid_filter<'a>(is_solid: &'a dyn Fn(Entity) -> bool) -> QueryFilter<'a> {
+    QueryFilter::new()
+        .groups(CollisionGroups::new(COLLISION_GROUP, COLLISION_GROUP))
+        .predicate(is_solid)
+}
+
+/// The `patrol` field of an entity, in the same space as its transform
+fn patrol_points<'a>(
+    instance: &'a EntityInstance,
+    transform: &'a Transform,
+) -> impl Iterator<Item = Vec2> + 'a {
+    instance
+        .field_instances
+        .iter()
+        .filter(|field| field.identifier == "patrol")
+        .filter_map(|field| match &field.value {
+            FieldValue::Points(points) => Some(points),
+            _ => None,
+        })
+        .flatten()
+        .flatten()
+        .map(|&point| grid_point(instance, transform, point))
+}
+
+/// The direction from an entity towards its `aim` field
+fn aim_direction(instance: &EntityInstance, transform: &Transform) -> Option<Vec2> {
+    instance
+        .field_instances
+        .iter()
+        .filter(|field| field.identifier == "aim")
+        .find_map(|field| match field.value {
+            FieldValue::Point(point) => point,
+            _ => None,
+        })
+        .map(|point| grid_point(instance, transform, point) - transform.translation.xy())
+        .filter(|&direction| direction != Vec2::ZERO)
+        .map(Vec2::normalize)
+}
+
+/// An LDtk grid point, in the same space as the entity's transform
+fn grid_point(instance: &EntityInstance, transform: &Transform, point: IVec2) -> Vec2 {
+    // LDtk grid coordinates point down
+    let offset = (point - instance.grid).as_vec2() * vec2(GRID_SIZE, -GRID_SIZE);
+    transform.translation.xy() + offset
+}
+
+/// The first string field of an entity with the given identifier
+fn string_field<'a>(instance: &'a EntityInstance, identifier: &str) -> Option<&'a str> {
+    instance
+        .field_instances
+        .iter()
+        .filter(|field| field.identifier == identifier)
+        .find_map(|field| match &field.value {
+            FieldValue::String(Some(value)) => Some(value.as_str()),
+            _ => None,
+        })
+}
+
+/// The first float field of an entity with the given identifier
+fn float_field(instance: &EntityInstance, identifier: &str) -> Option<f32> {
+    instance
+        .field_instances
+        .iter()
+        .filter(|field| field.identifier == identifier)
+        .find_map(|field| match field.value {
+            FieldValue::Float(value) => value,
+            _ => None,
+        })
+}
+
+/// The first int field of an entity with the given identifier
+fn int_field(instance: &EntityInstance, identifier: &str) -> Option<i32> {
+    instance
+        .field_instances
+        .iter()
+        .filter(|field| field.identifier == identifier)
+        .find_map(|field| match field.value {
+            FieldValue::Int(value) => value,
+            _ => None,
+        })
+}
+
+/// The first bool field of an entity with the given identifier
+fn bool_field(instance: &EntityInstance, identifier: &str) -> Option<bool> {
+    instance
+        .field_instances
+        .iter()
+        .filter(|field| field.identifier == identifier)
+        .find_map(|field| match field.value {
+            FieldValue::Bool(value) => Some(value),
+            _ => None,
+        })
+}
+
+/// The `archetype` field of an entity
+fn archetype_name(instance: &EntityInstance) -> &str {
+    string_field(instance, "archetype").unwrap_or(EnemyArchetype::DEFAULT)
+}
+
+/// The entity iids in the `targets` field of a signal node
+fn signal_targets(instance: &EntityInstance) -> impl Iterator<Item = &String> {
+    instance
+        .field_instances
+        .iter()
+        .filter(|field| field.identifier == "targets")
+        .filter_map(|field| match &field.value {
+            FieldValue::EntityRefs(entity_refs) => Some(entity_refs),
+            _ => None,
+        })
+        .flatten()
+        .flatten()
+        .map(|entity_ref| &entity_ref.entity_iid)
+}
+
 pub fn populate_level(
     mut commands: Commands,
     mut despawn: ResMut<DespawnSet>,
+    ass: Res<AssetServer>,
     actor_assets: Res<ActorAssets>,
+    archetype_assets: Res<ArchetypeAssets>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
     gate_assets: Res<GateAssets>,
     plate_assets: Res<PlateAssets>,
     vfx_assets: Res<VfxAssets>,
     entity_query: Query<(Entity, &Parent, &Transform, &EntityInstance), Added<EntityInstance>>,
     tile_query: Query<(&Parent, &Transform, &TileEnumTags), Added<TileEnumTags>>,
     playthrough: Res<Playthrough>,
+    mut rng: ResMut<GameRng>,
 ) {
-    let mut gate_map = HashMap::new();
+    let mut iid_map = HashMap::new();
+
+    for (entity, parent, &transform, instance) in &entity_query {
+        // Despawn marker entity
+        despawn.recursive(entity);
 
-    for (_, parent, &transform, instance) in &entity_query {
         let entity = match instance.identifier.as_str() {
             "player" => PlayerTemplate {
                 transform,
                 current_health: playthrough.health.unwrap_or(200.0),
+                inventory: playthrough
+                    .inventory
+                    .clone()
+                    .unwrap_or_else(Inventory::starting),
                 texture: if playthrough.defected {
                     actor_assets.gnoll_blue.clone()
                 } else {
@@ -102,76 +282,155 @@ pub fn populate_level(
                 ..default()
             }
             .spawn(&mut commands, &actor_assets, &vfx_assets),
-            "enemy" => EnemyTemplate {
+            "enemy" => {
+                // The enemy's post is the first patrol point
+                let patrol = [transform.translation.xy()]
+                    .into_iter()
+                    .chain(patrol_points(instance, &transform))
+                    .map(|point| {
+                        let point = PatrolPointTemplate {
+                            transform: Transform::from_translation(point.extend(0.0)),
+                        }
+                        .spawn(&mut commands);
+                        commands.entity(parent.get()).add_child(point);
+                        point
+                    })
+                    .collect();
+
+                let name = archetype_name(instance);
+                let archetype = archetypes
+                    .get(&archetype_assets.enemies)
+                    .map(|archetypes| archetypes.get(name))
+                    .unwrap_or_default();
+
+                EnemyTemplate {
+                    transform,
+                    patrol,
+                    ..default()
+                }
+                .with_archetype(name, &archetype, &ass)
+                .with_random_name(&mut *rng)
+                .spawn(&mut commands, &actor_assets, &vfx_assets)
+            },
+            "corpse" => {
+                let corpse = EnemyTemplate {
+                    transform,
+                    ..default()
+                }
+                .with_random_name(&mut *rng)
+                .dead()
+                .spawn(&mut commands, &actor_assets, &vfx_assets);
+
+                commands.entity(corpse).insert((
+                    Interactable::new("Search"),
+                    Searchable {
+                        key: string_field(instance, "key").map(str::to_string),
+                    },
+                ));
+                corpse
+            },
+            "open_gate" => GateTemplate {
                 transform,
-                ..default()
+                open: true,
             }
-            .with_random_name()
-            .spawn(&mut commands, &actor_assets, &vfx_assets),
-            "corpse" => EnemyTemplate {
+            .spawn(&mut commands, &gate_assets),
+            "closed_gate" => GateTemplate {
                 transform,
-                ..default()
+                open: false,
             }
-            .with_random_name()
-            .dead()
-            .spawn(&mut commands, &actor_assets, &vfx_assets),
-            "open_gate" => {
-                let gate = GateTemplate {
-                    transform,
-                    open: true,
-                }
-                .spawn(&mut commands, &gate_assets);
+            .spawn(&mut commands, &gate_assets),
+            "locked_door" => DoorTemplate {
+                transform,
+                key: string_field(instance, "key")
+                    .unwrap_or("bronze")
+                    .to_string(),
+            }
+            .spawn(&mut commands, &gate_assets),
+            "lever" => LeverTemplate { transform }.spawn(&mut commands),
+            "plate" => PlateTemplate {
+                transform,
+                threshold: float_field(instance, "threshold").unwrap_or(5.0),
+                latch: bool_field(instance, "latch").unwrap_or(false),
+            }
+            .spawn(&mut commands, &plate_assets),
+            "timer" => SignalTimerTemplate {
+                transform,
+                period: float_field(instance, "period").unwrap_or(2.0),
+                on_time: float_field(instance, "on_time").unwrap_or(1.0),
+            }
+            .spawn(&mut commands),
+            "logic" => {
+                let op = string_field(instance, "op").unwrap_or("or");
+                let delay = float_field(instance, "delay").unwrap_or(1.0);
+                let logic = Logic::from_op(op, delay).unwrap_or_else(|| {
+                    warn!("Unknown logic op: {op}");
+                    Logic::Or
+                });
 
-                gate_map.insert(&instance.iid, gate);
-                gate
+                LogicTemplate { transform, logic }.spawn(&mut commands)
             },
-            "closed_gate" => {
-                let gate = GateTemplate {
+            "spawner" => SpawnerTemplate {
+                transform,
+                archetype: archetype_name(instance).to_string(),
+            }
+            .spawn(&mut commands),
+            "pickup" => {
+                let name = string_field(instance, "item").unwrap_or("potion");
+                let key = string_field(instance, "key");
+                let Some(item) = Item::from_name(name, key) else {
+                    warn!("Unknown pickup item: {name}");
+                    continue;
+                };
+
+                PickupTemplate {
                     transform,
-                    open: false,
+                    item,
+                    count: int_field(instance, "count").unwrap_or(1).max(1) as u32,
                 }
-                .spawn(&mut commands, &gate_assets);
-
-                gate_map.insert(&instance.iid, gate);
-                gate
+                .spawn(&mut commands)
             },
+            "spikes" => SpikesTemplate {
+                transform,
+                damage: float_field(instance, "damage").unwrap_or(10.0),
+                raised: bool_field(instance, "raised").unwrap_or(true),
+            }
+            .spawn(&mut commands),
+            "turret" => TurretTemplate {
+                transform,
+                direction: aim_direction(instance, &transform).unwrap_or(Vec2::NEG_Y),
+                damage: float_field(instance, "damage").unwrap_or(8.0),
+                cooldown: float_field(instance, "cooldown").unwrap_or(1.0),
+            }
+            .spawn(&mut commands),
+            "portcullis" => PortcullisTemplate {
+                transform,
+                damage: float_field(instance, "damage").unwrap_or(40.0),
+            }
+            .spawn(&mut commands, &gate_assets),
             "exit" => ExitTemplate { transform }.spawn(&mut commands),
             "victory" => VictorySquareTemplate { transform }.spawn(&mut commands),
             _ => continue,
         };
         commands.entity(parent.get()).add_child(entity);
+        iid_map.insert(&instance.iid, entity);
     }
 
-    // Spawn plates last so they can link to gates by Entity
-    for (entity, parent, &transform, instance) in &entity_query {
-        // Despawn marker entity
-        despawn.recursive(entity);
-
-        let entity = match instance.identifier.as_str() {
-            "plate" => {
-                let mut gates = vec![];
-                for field in &instance.field_instances {
-                    if field.identifier != "gates" {
-                        continue;
-                    }
-                    let FieldValue::EntityRefs(entity_refs) = &field.value else {
-                        continue;
-                    };
-
-                    gates.extend(
-                        entity_refs
-                            .iter()
-                            .filter_map(|x| x.as_ref())
-                            .filter_map(|entity_ref| gate_map.get(&entity_ref.entity_iid)),
-                    );
-                    break;
-                }
-
-                PlateTemplate { transform, gates }.spawn(&mut commands, &plate_assets)
-            },
-            _ => continue,
+    // Link signal nodes once every entity has been spawned
+    for (_, _, _, instance) in &entity_query {
+        let Some(&entity) = iid_map.get(&instance.iid) else {
+            continue;
         };
-        commands.entity(parent.get()).add_child(entity);
+        let targets = signal_targets(instance)
+            .filter_map(|iid| iid_map.get(iid))
+            .copied()
+            .collect::<Vec<_>>();
+        if targets.is_empty() {
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .insert(Signal { on: false, targets });
     }
 
     for (parent, &transform, tile) in &tile_query {
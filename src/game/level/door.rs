@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+use crate::common::audio::SfxChannel;
+use crate::common::UpdateSet;
+use crate::game::interact::InteractEvent;
+use crate::game::interact::Interactable;
+use crate::game::inventory::Inventory;
+use crate::game::level::gate::Gate;
+use crate::game::level::gate::GateAssets;
+use crate::game::level::gate::GateTemplate;
+use crate::game::level::lever::LeverAssets;
+
+pub struct DoorPlugin;
+
+impl Plugin for DoorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Lock>()
+            .add_systems(FixedUpdate, unlock_doors.in_set(UpdateSet::HandleEvents));
+    }
+}
+
+/// Keeps a gate shut until an actor with the matching key interacts with it
+#[derive(Component, Reflect)]
+pub struct Lock {
+    pub key: String,
+}
+
+fn unlock_doors(
+    mut commands: Commands,
+    mut interact_events: EventReader<InteractEvent>,
+    mut door_query: Query<(&Lock, &mut Gate)>,
+    inventory_query: Query<&Inventory>,
+    lever_assets: Res<LeverAssets>,
+    audio: Res<AudioChannel<SfxChannel>>,
+) {
+    for &InteractEvent { actor, target } in interact_events.read() {
+        let Ok((lock, mut gate)) = door_query.get_mut(target) else {
+            continue;
+        };
+        if !inventory_query
+            .get(actor)
+            .is_ok_and(|inventory| inventory.has_key(&lock.key))
+        {
+            continue;
+        }
+
+        // Swing the door away from its default state for good
+        gate.default_open = !gate.default_open;
+        commands.entity(target).remove::<(Lock, Interactable)>();
+        audio
+            .play(lever_assets.pull.clone())
+            .with_volume(SfxChannel::SWITCH);
+    }
+}
+
+pub struct DoorTemplate {
+    pub transform: Transform,
+    pub key: String,
+}
+
+impl DoorTemplate {
+    pub fn spawn(self, commands: &mut Commands, gate_assets: &GateAssets) -> Entity {
+        let door = GateTemplate {
+            transform: self.transform,
+            open: false,
+        }
+        .spawn(commands, gate_assets);
+
+        commands.entity(door).insert((
+            Name::new("Door"),
+            Interactable::new(format!("Unlock ({} key)", self.key)),
+            Lock { key: self.key },
+        ));
+
+        door
+    }
+}
@@ -8,13 +8,14 @@ use crate::game::actor::player::PlayerControl;
 use crate::game::actor::player::Playthrough;
 use crate::game::combat::COLLISION_GROUP;
 use crate::game::combat::PLAYER_HURTBOX_GROUP;
+use crate::game::inventory::Inventory;
 
 pub struct ExitPlugin;
 
 impl Plugin for ExitPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Exit>()
-            .add_systems(Update, detect_exit.in_set(UpdateSet::Start));
+            .add_systems(FixedUpdate, detect_exit.in_set(UpdateSet::Start));
     }
 }
 
@@ -24,14 +25,14 @@ pub struct Exit;
 fn detect_exit(
     mut collision_events: EventReader<CollisionEvent>,
     mut level_selection: ResMut<LevelSelection>,
-    player_query: Query<&Health, With<PlayerControl>>,
+    player_query: Query<(&Health, &Inventory), With<PlayerControl>>,
     mut playthrough: ResMut<Playthrough>,
     exit_query: Query<(), With<Exit>>,
 ) {
     let LevelSelection::Indices(idx) = *level_selection else {
         return;
     };
-    let Ok(player_health) = player_query.get_single() else {
+    let Ok((player_health, player_inventory)) = player_query.get_single() else {
         return;
     };
 
@@ -42,6 +43,7 @@ fn detect_exit(
         if exit_query.contains(entity1) || exit_query.contains(entity2) {
             *level_selection = LevelSelection::Indices(LevelIndices::in_root(idx.level + 1));
             playthrough.health = Some(player_health.current);
+            playthrough.inventory = Some(player_inventory.clone());
             break;
         }
     }
@@ -2,7 +2,10 @@ use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 use bevy_rapier2d::prelude::*;
 
+use crate::common::UpdateSet;
 use crate::game::combat::COLLISION_GROUP;
+use crate::game::level::signal::Signal;
+use crate::game::noise::NoiseEvent;
 
 pub struct GatePlugin;
 
@@ -11,7 +14,8 @@ impl Plugin for GatePlugin {
         app.register_type::<GateAssets>()
             .init_collection::<GateAssets>();
 
-        app.register_type::<Gate>();
+        app.register_type::<Gate>()
+            .add_systems(FixedUpdate, update_gates.in_set(UpdateSet::HandleEvents));
     }
 }
 
@@ -24,9 +28,44 @@ pub struct GateAssets {
     pub closed: Handle<Image>,
 }
 
+/// A signal sink that toggles away from its default state while its signal is on
 #[derive(Component, Reflect)]
 pub struct Gate {
     pub open: bool,
+    pub default_open: bool,
+}
+
+fn update_gates(
+    mut gate_query: Query<
+        (
+            &mut Gate,
+            &Signal,
+            &mut Handle<Image>,
+            &mut CollisionGroups,
+            &GlobalTransform,
+        ),
+        Or<(Changed<Signal>, Changed<Gate>)>,
+    >,
+    gate_assets: Res<GateAssets>,
+    mut noise_events: EventWriter<NoiseEvent>,
+) {
+    for (mut gate, signal, mut image, mut groups, gt) in &mut gate_query {
+        let open = gate.default_open != signal.on;
+        if gate.open == open {
+            continue;
+        }
+
+        gate.open = open;
+        (groups.filters, *image) = if gate.open {
+            (Group::empty(), gate_assets.open.clone())
+        } else {
+            (COLLISION_GROUP, gate_assets.closed.clone())
+        };
+        noise_events.send(NoiseEvent {
+            position: gt.translation().xy(),
+            loudness: 250.0,
+        });
+    }
 }
 
 pub struct GateTemplate {
@@ -57,7 +96,11 @@ impl GateTemplate {
                 },
                 Friction::new(0.0),
                 RigidBody::Fixed,
-                Gate { open: self.open },
+                Gate {
+                    open: self.open,
+                    default_open: self.open,
+                },
+                Signal::default(),
             ))
             .id()
     }
@@ -0,0 +1,295 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::common::UpdateSet;
+use crate::game::combat::hazard_hitbox_groups;
+use crate::game::combat::projectile::ProjectileTemplate;
+use crate::game::combat::projectile::RangedAttack;
+use crate::game::combat::status::StatusEffect;
+use crate::game::combat::status::StatusKind;
+use crate::game::combat::CombatAssets;
+use crate::game::combat::HitEffects;
+use crate::game::level::gate::Gate;
+use crate::game::level::gate::GateAssets;
+use crate::game::level::gate::GateTemplate;
+use crate::game::level::signal::Signal;
+use crate::util::animation::lifetime::Lifetime;
+
+pub struct HazardPlugin;
+
+impl Plugin for HazardPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Spikes>().add_systems(
+            FixedUpdate,
+            (
+                rearm_spikes.in_set(UpdateSet::Update),
+                update_spikes.in_set(UpdateSet::HandleEvents),
+            ),
+        );
+
+        app.register_type::<Turret>()
+            .add_systems(FixedUpdate, fire_turrets.in_set(UpdateSet::HandleEvents));
+
+        app.register_type::<Portcullis>().add_systems(
+            FixedUpdate,
+            drop_portcullises.in_set(UpdateSet::HandleEvents),
+        );
+    }
+}
+
+/// A signal sink that toggles away from its default state while its signal is on, and hurts
+/// whoever steps onto it while raised
+#[derive(Component, Reflect)]
+pub struct Spikes {
+    pub raised: bool,
+    pub default_raised: bool,
+}
+
+impl Spikes {
+    const RAISED_COLOR: Color = Color::rgb(0.75, 0.75, 0.8);
+    const LOWERED_COLOR: Color = Color::rgb(0.3, 0.3, 0.35);
+}
+
+fn update_spikes(
+    mut commands: Commands,
+    mut spikes_query: Query<
+        (Entity, &mut Spikes, &Signal, &mut HitEffects, &mut Sprite),
+        Changed<Signal>,
+    >,
+) {
+    for (entity, mut spikes, signal, mut hit, mut sprite) in &mut spikes_query {
+        let raised = spikes.default_raised != signal.on;
+        if spikes.raised == raised {
+            continue;
+        }
+
+        spikes.raised = raised;
+        if raised {
+            commands.entity(entity).remove::<ColliderDisabled>();
+            sprite.color = Spikes::RAISED_COLOR;
+        } else {
+            // Each raise can hit everyone on the spikes again
+            commands.entity(entity).insert(ColliderDisabled);
+            hit.targets.clear();
+            sprite.color = Spikes::LOWERED_COLOR;
+        }
+    }
+}
+
+// Stepping off the spikes lets them hit again on the next step
+fn rearm_spikes(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut spikes_query: Query<&mut HitEffects, With<Spikes>>,
+) {
+    for &event in collision_events.read() {
+        let CollisionEvent::Stopped(entity1, entity2, _) = event else {
+            continue;
+        };
+
+        for (spikes, target) in [(entity1, entity2), (entity2, entity1)] {
+            let Ok(mut hit) = spikes_query.get_mut(spikes) else {
+                continue;
+            };
+            hit.targets.retain(|&x| x != target);
+        }
+    }
+}
+
+pub struct SpikesTemplate {
+    pub transform: Transform,
+    pub damage: f32,
+    pub raised: bool,
+}
+
+impl SpikesTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        let spikes = commands
+            .spawn((
+                Name::new("Spikes"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: if self.raised {
+                            Spikes::RAISED_COLOR
+                        } else {
+                            Spikes::LOWERED_COLOR
+                        },
+                        custom_size: Some(Vec2::splat(12.0)),
+                        ..default()
+                    },
+                    transform: self.transform,
+                    ..default()
+                },
+                Collider::cuboid(6.0, 6.0),
+                Sensor,
+                hazard_hitbox_groups(),
+                ActiveEvents::COLLISION_EVENTS,
+                HitEffects {
+                    damage: self.damage,
+                    status: vec![StatusEffect {
+                        kind: StatusKind::Bleed(2.0),
+                        duration: 2.0,
+                    }],
+                    ..default()
+                },
+                Spikes {
+                    raised: self.raised,
+                    default_raised: self.raised,
+                },
+                Signal::default(),
+            ))
+            .id();
+
+        if !self.raised {
+            commands.entity(spikes).insert(ColliderDisabled);
+        }
+
+        spikes
+    }
+}
+
+/// A signal sink that fires projectiles in a fixed direction while its signal is on
+#[derive(Component, Reflect)]
+pub struct Turret {
+    pub direction: Vec2,
+    pub attack: RangedAttack,
+}
+
+fn fire_turrets(
+    mut commands: Commands,
+    mut turret_query: Query<(&mut Turret, &Signal, &GlobalTransform)>,
+    combat_assets: Res<CombatAssets>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    for (mut turret, signal, gt) in &mut turret_query {
+        turret.attack.cooldown_t -= dt;
+        if !signal.on || turret.attack.cooldown_t > 0.0 {
+            continue;
+        }
+        turret.attack.cooldown_t = turret.attack.cooldown;
+
+        // Fire from the edge of the turret so it doesn't shoot itself
+        let position = gt.translation() + (8.0 * turret.direction).extend(0.0);
+        let attack = &turret.attack;
+        ProjectileTemplate {
+            position,
+            direction: turret.direction,
+            speed: attack.speed,
+            radius: attack.radius,
+            damage: attack.damage,
+            knockback: attack.knockback,
+            lifetime: attack.lifetime,
+            pierce: attack.pierce,
+            bounce: attack.bounce,
+            status: attack.status.clone(),
+            groups: hazard_hitbox_groups(),
+            source: None,
+        }
+        .spawn(&mut commands, &combat_assets);
+    }
+}
+
+pub struct TurretTemplate {
+    pub transform: Transform,
+    pub direction: Vec2,
+    pub damage: f32,
+    pub cooldown: f32,
+}
+
+impl TurretTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("Turret"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgb(0.35, 0.3, 0.25),
+                        custom_size: Some(Vec2::splat(10.0)),
+                        ..default()
+                    },
+                    transform: self.transform,
+                    ..default()
+                },
+                Turret {
+                    direction: self.direction,
+                    attack: RangedAttack {
+                        speed: 300.0,
+                        radius: 2.0,
+                        damage: self.damage,
+                        knockback: 3.0,
+                        lifetime: 2.0,
+                        status: vec![],
+                        cooldown: self.cooldown,
+                        ..default()
+                    },
+                },
+                Signal::default(),
+            ))
+            .id()
+    }
+}
+
+/// A gate that crushes whoever is under it when it slams shut
+#[derive(Component, Reflect)]
+pub struct Portcullis {
+    pub damage: f32,
+    /// Whether the gate was open on the previous tick
+    pub open: bool,
+}
+
+fn drop_portcullises(
+    mut commands: Commands,
+    mut portcullis_query: Query<(&mut Portcullis, &Gate, &GlobalTransform), Changed<Gate>>,
+) {
+    for (mut portcullis, gate, gt) in &mut portcullis_query {
+        let dropped = portcullis.open && !gate.open;
+        portcullis.open = gate.open;
+        if !dropped {
+            continue;
+        }
+
+        commands.spawn((
+            Name::new("PortcullisHitbox"),
+            TransformBundle::from_transform(Transform::from_translation(gt.translation())),
+            Collider::ball(8.0),
+            Sensor,
+            hazard_hitbox_groups(),
+            ActiveEvents::COLLISION_EVENTS,
+            HitEffects {
+                damage: portcullis.damage,
+                knockback: 4.0 * Vec2::NEG_Y,
+                status: vec![StatusEffect {
+                    kind: StatusKind::Knockdown,
+                    duration: 1.0,
+                }],
+                ..default()
+            },
+            Lifetime(0.2),
+        ));
+    }
+}
+
+pub struct PortcullisTemplate {
+    pub transform: Transform,
+    pub damage: f32,
+}
+
+impl PortcullisTemplate {
+    pub fn spawn(self, commands: &mut Commands, gate_assets: &GateAssets) -> Entity {
+        let portcullis = GateTemplate {
+            transform: self.transform,
+            open: true,
+        }
+        .spawn(commands, gate_assets);
+
+        commands.entity(portcullis).insert((
+            Name::new("Portcullis"),
+            Portcullis {
+                damage: self.damage,
+                open: true,
+            },
+        ));
+
+        portcullis
+    }
+}
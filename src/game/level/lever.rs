@@ -0,0 +1,131 @@
+use bevy::math::vec2;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy_asset_loader::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+use crate::common::audio::SfxChannel;
+use crate::common::UpdateSet;
+use crate::game::interact::InteractEvent;
+use crate::game::interact::Interactable;
+use crate::game::level::signal::Signal;
+use crate::game::level::signal::SignalSource;
+
+pub struct LeverPlugin;
+
+impl Plugin for LeverPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<LeverAssets>()
+            .init_collection::<LeverAssets>();
+
+        app.register_type::<Lever>()
+            .register_type::<LeverHandle>()
+            .add_systems(FixedUpdate, pull_levers.in_set(UpdateSet::HandleEvents))
+            .add_systems(Update, update_lever_handles.in_set(UpdateSet::Update));
+    }
+}
+
+#[derive(AssetCollection, Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct LeverAssets {
+    #[asset(path = "sound/sfx/button_gate.wav")]
+    pub pull: Handle<AudioSource>,
+}
+
+/// A signal source that toggles when interacted with
+#[derive(Component, Reflect)]
+pub struct Lever;
+
+fn pull_levers(
+    mut interact_events: EventReader<InteractEvent>,
+    mut lever_query: Query<&mut Signal, With<Lever>>,
+    lever_assets: Res<LeverAssets>,
+    audio: Res<AudioChannel<SfxChannel>>,
+) {
+    for &InteractEvent { target, .. } in interact_events.read() {
+        let Ok(mut signal) = lever_query.get_mut(target) else {
+            continue;
+        };
+
+        signal.on = !signal.on;
+        audio
+            .play(lever_assets.pull.clone())
+            .with_volume(SfxChannel::SWITCH);
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct LeverHandle;
+
+impl LeverHandle {
+    const ANGLE: f32 = 0.5;
+}
+
+fn update_lever_handles(
+    lever_query: Query<(&Signal, &Children), (With<Lever>, Changed<Signal>)>,
+    mut handle_query: Query<&mut Transform, With<LeverHandle>>,
+) {
+    for (signal, children) in &lever_query {
+        for &child in children {
+            let Ok(mut transform) = handle_query.get_mut(child) else {
+                continue;
+            };
+
+            let angle = if signal.on {
+                -LeverHandle::ANGLE
+            } else {
+                LeverHandle::ANGLE
+            };
+            transform.rotation = Quat::from_rotation_z(angle);
+        }
+    }
+}
+
+pub struct LeverTemplate {
+    pub transform: Transform,
+}
+
+impl LeverTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        // Children
+        let handle = commands
+            .spawn((
+                Name::new("Handle"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgb(0.55, 0.4, 0.25),
+                        custom_size: Some(vec2(2.0, 10.0)),
+                        // Pivot around the base of the handle
+                        anchor: Anchor::BottomCenter,
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0.0, -2.0, -0.001)
+                        .with_rotation(Quat::from_rotation_z(LeverHandle::ANGLE)),
+                    ..default()
+                },
+                LeverHandle,
+            ))
+            .id();
+
+        // Parent
+        commands
+            .spawn((
+                Name::new("Lever"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgb(0.3, 0.3, 0.35),
+                        custom_size: Some(vec2(8.0, 4.0)),
+                        ..default()
+                    },
+                    transform: self.transform,
+                    ..default()
+                },
+                Interactable::new("Pull"),
+                Lever,
+                Signal::default(),
+                SignalSource,
+            ))
+            .add_child(handle)
+            .id()
+    }
+}
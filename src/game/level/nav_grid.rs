@@ -0,0 +1,145 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy::utils::HashSet;
+
+use crate::common::UpdateSet;
+use crate::game::level::gate::Gate;
+use crate::game::level::wall::Wall;
+use crate::game::level::GRID_SIZE;
+
+pub struct NavGridPlugin;
+
+impl Plugin for NavGridPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavGrid>()
+            .add_systems(FixedUpdate, update_nav_grid.in_set(UpdateSet::Update));
+    }
+}
+
+/// The grid cells that are blocked by walls and closed gates
+#[derive(Resource, Default)]
+pub struct NavGrid {
+    blocked: HashSet<IVec2>,
+    /// The world position of a cell corner (levels aren't always aligned to the grid)
+    origin: Vec2,
+}
+
+impl NavGrid {
+    /// Give up on finding a path after visiting this many cells
+    const MAX_VISITED: usize = 4096;
+
+    pub fn cell(&self, position: Vec2) -> IVec2 {
+        ((position - self.origin) / GRID_SIZE).floor().as_ivec2()
+    }
+
+    pub fn center(&self, cell: IVec2) -> Vec2 {
+        self.origin + (cell.as_vec2() + 0.5) * GRID_SIZE
+    }
+
+    pub fn is_blocked(&self, cell: IVec2) -> bool {
+        self.blocked.contains(&cell)
+    }
+
+    /// Find a path of cell centers with A*, excluding the starting cell
+    pub fn find_path(&self, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        // Costs are scaled by 10 to keep them integral
+        const STRAIGHT: u32 = 10;
+        const DIAGONAL: u32 = 14;
+        let heuristic = |cell: IVec2, goal: IVec2| {
+            let delta = (goal - cell).abs();
+            let (min, max) = (delta.min_element() as u32, delta.max_element() as u32);
+            DIAGONAL * min + STRAIGHT * (max - min)
+        };
+
+        let start = self.cell(from);
+        let goal = self.cell(to);
+        if self.is_blocked(goal) {
+            return None;
+        }
+
+        // Cell -> (previous cell, cost from start)
+        let mut visited = HashMap::from_iter([(start, (start, 0))]);
+        let mut open = BinaryHeap::from([(Reverse(heuristic(start, goal)), start.x, start.y)]);
+        while let Some((_, x, y)) = open.pop() {
+            let cell = IVec2::new(x, y);
+            if cell == goal {
+                let mut path = vec![];
+                let mut cell = goal;
+                while cell != start {
+                    path.push(self.center(cell));
+                    cell = visited[&cell].0;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if visited.len() > Self::MAX_VISITED {
+                return None;
+            }
+
+            let cost = visited[&cell].1;
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    let step = IVec2::new(dx, dy);
+                    let next = cell + step;
+                    if step == IVec2::ZERO || self.is_blocked(next) {
+                        continue;
+                    }
+                    // Don't cut corners
+                    let diagonal = dx != 0 && dy != 0;
+                    if diagonal
+                        && (self.is_blocked(cell + IVec2::new(dx, 0))
+                            || self.is_blocked(cell + IVec2::new(0, dy)))
+                    {
+                        continue;
+                    }
+
+                    let next_cost = cost + if diagonal { DIAGONAL } else { STRAIGHT };
+                    if visited
+                        .get(&next)
+                        .is_some_and(|&(_, old_cost)| old_cost <= next_cost)
+                    {
+                        continue;
+                    }
+                    visited.insert(next, (cell, next_cost));
+                    open.push((Reverse(next_cost + heuristic(next, goal)), next.x, next.y));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// Rebuild the grid when walls are spawned or despawned, or when a gate opens or closes
+fn update_nav_grid(
+    mut nav_grid: ResMut<NavGrid>,
+    wall_query: Query<&GlobalTransform, With<Wall>>,
+    gate_query: Query<(&Gate, &GlobalTransform)>,
+    changed_query: Query<(), (Or<(With<Wall>, With<Gate>)>, Changed<GlobalTransform>)>,
+    changed_gate_query: Query<(), Changed<Gate>>,
+    mut removed_walls: RemovedComponents<Wall>,
+    mut removed_gates: RemovedComponents<Gate>,
+) {
+    let removed = removed_walls.read().count() + removed_gates.read().count() > 0;
+    if !removed && changed_query.is_empty() && changed_gate_query.is_empty() {
+        return;
+    }
+
+    if let Some(gt) = wall_query.iter().next() {
+        nav_grid.origin =
+            (gt.translation().xy() - GRID_SIZE / 2.0).rem_euclid(Vec2::splat(GRID_SIZE));
+    }
+    let closed_gates = gate_query
+        .iter()
+        .filter(|(gate, _)| !gate.open)
+        .map(|(_, gt)| gt);
+    let blocked = wall_query
+        .iter()
+        .chain(closed_gates)
+        .map(|gt| nav_grid.cell(gt.translation().xy()))
+        .collect();
+    nav_grid.blocked = blocked;
+}
@@ -3,11 +3,11 @@ use bevy_asset_loader::prelude::*;
 use bevy_kira_audio::prelude::*;
 use bevy_rapier2d::prelude::*;
 
+use crate::common::audio::SfxChannel;
 use crate::common::UpdateSet;
 use crate::game::combat::COLLISION_GROUP;
-use crate::game::combat::PLAYER_HURTBOX_GROUP;
-use crate::game::level::gate::Gate;
-use crate::game::level::gate::GateAssets;
+use crate::game::level::signal::Signal;
+use crate::game::level::signal::SignalSource;
 
 pub struct PlatePlugin;
 
@@ -17,7 +17,7 @@ impl Plugin for PlatePlugin {
             .init_collection::<PlateAssets>();
 
         app.register_type::<Plate>()
-            .add_systems(Update, activate_plates.in_set(UpdateSet::Start));
+            .add_systems(FixedUpdate, activate_plates.in_set(UpdateSet::Start));
     }
 }
 
@@ -30,65 +30,72 @@ pub struct PlateAssets {
     unpressed: Handle<Image>,
 
     #[asset(path = "sound/sfx/button_gate.wav")]
-    trigger_gate: Handle<AudioSource>,
+    press: Handle<AudioSource>,
+    #[asset(path = "sound/sfx/pop_2.wav")]
+    release: Handle<AudioSource>,
 }
 
-#[derive(Component, Reflect, Default)]
+/// A signal source that stays on while enough mass is standing on it
+#[derive(Component, Reflect)]
 pub struct Plate {
-    gates: Vec<Entity>,
-    pressed: bool,
+    /// The total mass needed to press the plate
+    pub threshold: f32,
+    /// Stay pressed forever after the first press
+    pub latch: bool,
+}
+
+impl Default for Plate {
+    fn default() -> Self {
+        Self {
+            threshold: 5.0,
+            latch: false,
+        }
+    }
 }
 
 fn activate_plates(
-    mut collision_events: EventReader<CollisionEvent>,
-    mut plate_query: Query<(&mut Plate, &mut Handle<Image>)>,
-    mut gate_query: Query<(&mut Gate, &mut Handle<Image>, &mut CollisionGroups), Without<Plate>>,
-    gate_assets: Res<GateAssets>,
+    mut plate_query: Query<(Entity, &Plate, &mut Signal, &mut Handle<Image>)>,
+    mass_query: Query<&ColliderMassProperties>,
+    rapier_context: Res<RapierContext>,
     plate_assets: Res<PlateAssets>,
-    audio: Res<Audio>,
+    audio: Res<AudioChannel<SfxChannel>>,
 ) {
-    for &event in collision_events.read() {
-        let CollisionEvent::Started(entity1, entity2, _) = event else {
+    for (entity, plate, mut signal, mut image) in &mut plate_query {
+        if plate.latch && signal.on {
             continue;
-        };
+        }
 
-        let mut handle_collision = |entity: Entity| {
-            let Ok((mut plate, mut plate_image)) = plate_query.get_mut(entity) else {
-                return;
-            };
-            if plate.pressed {
-                return;
-            }
-            plate.pressed = true;
-            *plate_image = plate_assets.pressed.clone();
+        // Players, enemies and corpses all weigh the plate down
+        let mass = rapier_context
+            .intersection_pairs_with(entity)
+            .filter(|&(_, _, intersecting)| intersecting)
+            .map(|(entity1, entity2, _)| if entity1 == entity { entity2 } else { entity1 })
+            .filter_map(|other| match mass_query.get(other) {
+                Ok(&ColliderMassProperties::Mass(mass)) => Some(mass),
+                _ => None,
+            })
+            .sum::<f32>();
 
-            audio
-                .play(plate_assets.trigger_gate.clone())
-                .with_volume(0.8);
-
-            for &entity in &plate.gates {
-                let Ok((mut gate, mut gate_image, mut gate_groups)) = gate_query.get_mut(entity)
-                else {
-                    continue;
-                };
+        let pressed = mass >= plate.threshold;
+        if signal.on == pressed {
+            continue;
+        }
+        signal.on = pressed;
 
-                gate.open = !gate.open;
-                (gate_groups.filters, *gate_image) = if gate.open {
-                    (Group::empty(), gate_assets.open.clone())
-                } else {
-                    (COLLISION_GROUP, gate_assets.closed.clone())
-                };
-            }
+        let (texture, sound) = if pressed {
+            (&plate_assets.pressed, &plate_assets.press)
+        } else {
+            (&plate_assets.unpressed, &plate_assets.release)
         };
-
-        handle_collision(entity1);
-        handle_collision(entity2);
+        *image = texture.clone();
+        audio.play(sound.clone()).with_volume(SfxChannel::SWITCH);
     }
 }
 
 pub struct PlateTemplate {
     pub transform: Transform,
-    pub gates: Vec<Entity>,
+    pub threshold: f32,
+    pub latch: bool,
 }
 
 impl PlateTemplate {
@@ -102,16 +109,19 @@ impl PlateTemplate {
                     ..default()
                 },
                 Collider::ball(2.0),
+                // Actors drop their hurtbox groups while dodging, but never their collision group
                 CollisionGroups {
                     memberships: COLLISION_GROUP,
-                    filters: PLAYER_HURTBOX_GROUP,
+                    filters: COLLISION_GROUP,
                 },
                 Sensor,
                 ActiveEvents::COLLISION_EVENTS,
                 Plate {
-                    gates: self.gates,
-                    ..default()
+                    threshold: self.threshold,
+                    latch: self.latch,
                 },
+                Signal::default(),
+                SignalSource,
             ))
             .id()
     }
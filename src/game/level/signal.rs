@@ -0,0 +1,179 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::common::UpdateSet;
+
+pub struct SignalPlugin;
+
+impl Plugin for SignalPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Signal>()
+            .register_type::<SignalSource>()
+            .register_type::<Logic>()
+            .add_systems(FixedUpdate, propagate_signals.in_set(UpdateSet::Update));
+
+        app.register_type::<SignalTimer>().add_systems(
+            FixedUpdate,
+            update_signal_timers
+                .in_set(UpdateSet::Update)
+                .before(propagate_signals),
+        );
+    }
+}
+
+/// A node in the level's signal graph, linked to its targets through the LDtk `targets` field
+///
+/// `on` is only written when it changes, so `Changed<Signal>` marks a rising or falling edge.
+#[derive(Component, Reflect, Default)]
+pub struct Signal {
+    pub on: bool,
+    pub targets: Vec<Entity>,
+}
+
+/// Marks a node that sets its own signal instead of reading its inputs (e.g. plates and timers)
+#[derive(Component, Reflect, Default)]
+pub struct SignalSource;
+
+/// How a node combines its inputs (nodes without `Logic` act as `Or`)
+#[derive(Component, Reflect, Clone)]
+pub enum Logic {
+    And,
+    Or,
+    Not,
+    /// Passes its input on once it has held steady for `delay` seconds
+    Delay {
+        delay: f32,
+        t: f32,
+    },
+    /// Stays on forever after its first input
+    Latch,
+}
+
+impl Logic {
+    /// Parse the LDtk `op` field
+    pub fn from_op(op: &str, delay: f32) -> Option<Self> {
+        Some(match op {
+            "and" => Self::And,
+            "or" => Self::Or,
+            "not" => Self::Not,
+            "delay" => Self::Delay { delay, t: 0.0 },
+            "latch" => Self::Latch,
+            _ => return None,
+        })
+    }
+}
+
+// Signals travel one step per tick, reading their inputs from the previous tick
+fn propagate_signals(
+    mut node_query: Query<(Entity, &mut Signal, Option<&mut Logic>, Has<SignalSource>)>,
+    time: Res<Time>,
+) {
+    // Count (total, on) inputs for each target
+    let mut inputs = HashMap::<Entity, (usize, usize)>::new();
+    for (_, signal, ..) in &node_query {
+        for &target in &signal.targets {
+            let input = inputs.entry(target).or_default();
+            input.0 += 1;
+            input.1 += signal.on as usize;
+        }
+    }
+
+    let dt = time.delta_seconds();
+    for (entity, mut signal, mut logic, is_source) in &mut node_query {
+        if is_source {
+            continue;
+        }
+
+        let (total, on) = inputs.get(&entity).copied().unwrap_or_default();
+        let any = on > 0;
+        let next = match logic.as_deref().cloned() {
+            Some(Logic::And) => total > 0 && on == total,
+            Some(Logic::Not) => !any,
+            Some(Logic::Delay { delay, t }) => {
+                let mut next_t = if any == signal.on { 0.0 } else { t + dt };
+                let next = if next_t >= delay {
+                    next_t = 0.0;
+                    any
+                } else {
+                    signal.on
+                };
+
+                // Only write the timer while it runs, so that `Changed<Logic>` stays meaningful
+                if next_t != t {
+                    if let Some(Logic::Delay { t, .. }) = logic.as_deref_mut() {
+                        *t = next_t;
+                    }
+                }
+                next
+            },
+            Some(Logic::Latch) => signal.on || any,
+            Some(Logic::Or) | None => any,
+        };
+
+        if signal.on != next {
+            signal.on = next;
+        }
+    }
+}
+
+/// A source that switches on for `on_time` seconds out of every `period`
+#[derive(Component, Reflect)]
+pub struct SignalTimer {
+    pub period: f32,
+    pub on_time: f32,
+    pub t: f32,
+}
+
+fn update_signal_timers(mut timer_query: Query<(&mut SignalTimer, &mut Signal)>, time: Res<Time>) {
+    let dt = time.delta_seconds();
+    for (mut timer, mut signal) in &mut timer_query {
+        timer.t = (timer.t + dt) % timer.period.max(f32::EPSILON);
+
+        let on = timer.t < timer.on_time;
+        if signal.on != on {
+            signal.on = on;
+        }
+    }
+}
+
+pub struct SignalTimerTemplate {
+    pub transform: Transform,
+    pub period: f32,
+    pub on_time: f32,
+}
+
+impl SignalTimerTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("SignalTimer"),
+                TransformBundle::from_transform(self.transform),
+                Signal::default(),
+                SignalSource,
+                SignalTimer {
+                    period: self.period,
+                    on_time: self.on_time,
+                    t: 0.0,
+                },
+            ))
+            .id()
+    }
+}
+
+pub struct LogicTemplate {
+    pub transform: Transform,
+    pub logic: Logic,
+}
+
+impl LogicTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("Logic"),
+                TransformBundle::from_transform(self.transform),
+                Signal::default(),
+                self.logic,
+            ))
+            .id()
+    }
+}
@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+
+use crate::common::rng::GameRng;
+use crate::common::UpdateSet;
+use crate::game::actor::archetype::ArchetypeAssets;
+use crate::game::actor::archetype::EnemyArchetypes;
+use crate::game::actor::enemy::EnemyTemplate;
+use crate::game::actor::enemy::PatrolPointTemplate;
+use crate::game::actor::ActorAssets;
+use crate::game::level::signal::Signal;
+use crate::util::vfx::VfxAssets;
+
+pub struct SpawnerPlugin;
+
+impl Plugin for SpawnerPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Spawner>()
+            .add_systems(FixedUpdate, spawn_enemies.in_set(UpdateSet::HandleEvents));
+    }
+}
+
+/// A signal sink that spawns an enemy each time its signal switches on
+#[derive(Component, Reflect)]
+pub struct Spawner {
+    pub archetype: String,
+}
+
+fn spawn_enemies(
+    mut commands: Commands,
+    spawner_query: Query<(&Spawner, &Signal, &Transform, &Parent), Changed<Signal>>,
+    ass: Res<AssetServer>,
+    actor_assets: Res<ActorAssets>,
+    archetype_assets: Res<ArchetypeAssets>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+    vfx_assets: Res<VfxAssets>,
+    mut rng: ResMut<GameRng>,
+) {
+    for (spawner, signal, &transform, parent) in &spawner_query {
+        if !signal.on {
+            continue;
+        }
+
+        // The enemy's post is the spawner
+        let post = PatrolPointTemplate { transform }.spawn(&mut commands);
+        commands.entity(parent.get()).add_child(post);
+
+        let archetype = archetypes
+            .get(&archetype_assets.enemies)
+            .map(|archetypes| archetypes.get(&spawner.archetype))
+            .unwrap_or_default();
+        let enemy = EnemyTemplate {
+            transform,
+            patrol: vec![post],
+            ..default()
+        }
+        .with_archetype(&spawner.archetype, &archetype, &ass)
+        .with_random_name(&mut *rng)
+        .spawn(&mut commands, &actor_assets, &vfx_assets);
+        commands.entity(parent.get()).add_child(enemy);
+    }
+}
+
+pub struct SpawnerTemplate {
+    pub transform: Transform,
+    pub archetype: String,
+}
+
+impl SpawnerTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("Spawner"),
+                TransformBundle::from_transform(self.transform),
+                Signal::default(),
+                Spawner {
+                    archetype: self.archetype,
+                },
+            ))
+            .id()
+    }
+}
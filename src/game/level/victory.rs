@@ -12,7 +12,7 @@ impl Plugin for VictoryPlugin {
         app.register_type::<Victory>().init_resource::<Victory>();
 
         app.register_type::<VictorySquare>()
-            .add_systems(Update, detect_victory.in_set(UpdateSet::Start));
+            .add_systems(FixedUpdate, detect_victory.in_set(UpdateSet::Start));
     }
 }
 
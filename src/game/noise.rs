@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+
+pub struct NoisePlugin;
+
+impl Plugin for NoisePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<NoiseEvent>();
+    }
+}
+
+/// A sound that enemies can hear
+#[derive(Event)]
+pub struct NoiseEvent {
+    pub position: Vec2,
+    /// The distance at which the noise can be heard (before hearing sensitivity)
+    pub loudness: f32,
+}
@@ -0,0 +1,272 @@
+use bevy::prelude::*;
+use bevy::ui::Val::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::common::storage::SaveStorage;
+use crate::common::theme::ThemeBackgroundColor;
+use crate::common::theme::ThemeColor;
+use crate::game::actor::health::Health;
+use crate::game::actor::player::Playthrough;
+use crate::util::ui::font::PIXEL_FONT_HANDLE;
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunHistory>();
+
+        app.register_type::<HighScoreTable>();
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug)]
+pub struct Score {
+    pub alarm: i32,
+    pub health: i32,
+    pub time: i32,
+}
+
+impl Score {
+    pub fn new(alarm: f32, health: &Health, time: f32) -> Self {
+        let alarm_scale = 100_000.0;
+        let alarm_t = 1.0 - alarm;
+
+        let health_scale = 10_000.0;
+        let health_t = health.current / health.max;
+
+        let time_scale = 50_000.0 * 60.0;
+
+        Self {
+            alarm: (alarm_scale * alarm_t).round() as i32,
+            health: (health_scale * health_t).round() as i32,
+            time: (time_scale / time).round() as i32,
+        }
+    }
+
+    pub fn total(&self) -> i32 {
+        self.alarm + self.health + self.time
+    }
+}
+
+/// A finished run, as stored in the run history
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RunRecord {
+    pub victory: bool,
+    pub score: Score,
+    /// Seconds from the start of the run until it ended
+    pub elapsed: f32,
+    /// Seconds from the start of the run until the player defected
+    pub defection_time: Option<f32>,
+    pub kills: u32,
+    /// Seconds since the Unix epoch
+    pub date: u64,
+}
+
+impl RunRecord {
+    /// Record a run that ended at `end_time` (in fixed time)
+    pub fn now(victory: bool, score: Score, playthrough: &Playthrough, end_time: f32) -> Self {
+        Self {
+            victory,
+            score,
+            elapsed: end_time - playthrough.run_start_time,
+            defection_time: playthrough
+                .defected
+                .then(|| playthrough.start_time - playthrough.run_start_time),
+            kills: playthrough.kills,
+            date: unix_time(),
+        }
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct RunHistory(pub Vec<RunRecord>);
+
+impl FromWorld for RunHistory {
+    fn from_world(world: &mut World) -> Self {
+        let Some(text) = world.resource::<SaveStorage>().0.read(Self::KEY) else {
+            return default();
+        };
+
+        ron::from_str(&text).unwrap_or_else(|e| {
+            error!("Failed to parse run history: {e}");
+            default()
+        })
+    }
+}
+
+impl RunHistory {
+    const KEY: &'static str = "run_history.ron";
+    const HIGH_SCORE_COUNT: usize = 10;
+
+    pub fn add(&mut self, record: RunRecord, storage: &mut SaveStorage) {
+        self.0.push(record);
+
+        let result = ron::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|text| storage.0.write(Self::KEY, &text));
+        if let Err(e) = result {
+            error!("Failed to save run history: {e}");
+        }
+    }
+
+    /// The best victorious runs, sorted by total score
+    pub fn high_scores(&self) -> Vec<&RunRecord> {
+        let mut runs = self.0.iter().filter(|run| run.victory).collect::<Vec<_>>();
+        runs.sort_by_key(|run| std::cmp::Reverse(run.score.total()));
+        runs.truncate(Self::HIGH_SCORE_COUNT);
+        runs
+    }
+}
+
+#[cfg(not(feature = "web"))]
+fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "web")]
+fn unix_time() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+// See: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn format_date(unix_time: u64) -> String {
+    let z = (unix_time / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn format_duration(seconds: f32) -> String {
+    let seconds = seconds.max(0.0).round() as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[derive(Component, Reflect)]
+pub struct HighScoreTable;
+
+pub struct HighScoreTableTemplate<'a> {
+    pub history: &'a RunHistory,
+}
+
+impl HighScoreTableTemplate<'_> {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        let title_style = TextStyle {
+            font: PIXEL_FONT_HANDLE,
+            font_size: 24.0,
+            color: Color::WHITE,
+        };
+        let body_style = TextStyle {
+            font: PIXEL_FONT_HANDLE,
+            font_size: 12.0,
+            color: Color::WHITE,
+        };
+
+        let high_scores = self.history.high_scores();
+        let body = if high_scores.is_empty() {
+            "No escapes yet.".to_string()
+        } else {
+            high_scores
+                .into_iter()
+                .enumerate()
+                .map(|(i, run)| {
+                    format!(
+                        "{:>2}. {:>7}   {:>5}   {} kills   {}",
+                        i + 1,
+                        run.score.total(),
+                        format_duration(run.elapsed),
+                        run.kills,
+                        format_date(run.date),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n\n")
+        };
+
+        let text = commands
+            .spawn((
+                Name::new("HighScoreText"),
+                TextBundle::from_sections([
+                    TextSection::new("High Scores\n\n\n\n\n", title_style),
+                    TextSection::new(body, body_style),
+                ])
+                .with_text_justify(JustifyText::Center),
+            ))
+            .id();
+
+        commands
+            .spawn((
+                Name::new("HighScoreTable"),
+                NodeBundle {
+                    style: Style {
+                        margin: UiRect::all(Auto),
+                        padding: UiRect::all(Px(24.0)),
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    ..default()
+                },
+                ThemeBackgroundColor(ThemeColor::Popup),
+                HighScoreTable,
+            ))
+            .add_child(text)
+            .id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::storage::MemoryStorage;
+
+    fn record(victory: bool, alarm: i32) -> RunRecord {
+        RunRecord {
+            victory,
+            score: Score {
+                alarm,
+                health: 0,
+                time: 0,
+            },
+            elapsed: 90.0,
+            defection_time: Some(30.0),
+            kills: 3,
+            date: 0,
+        }
+    }
+
+    #[test]
+    fn run_history_round_trip() {
+        let mut world = World::new();
+        world.insert_resource(SaveStorage(Box::<MemoryStorage>::default()));
+        let mut history = RunHistory::from_world(&mut world);
+        assert!(history.0.is_empty());
+
+        let mut storage = world.remove_resource::<SaveStorage>().unwrap();
+        history.add(record(true, 10), &mut storage);
+        history.add(record(false, 30), &mut storage);
+        history.add(record(true, 20), &mut storage);
+
+        // Load the saved history back as if on the next launch
+        world.insert_resource(storage);
+        let loaded = RunHistory::from_world(&mut world);
+        assert_eq!(loaded.0.len(), 3);
+        assert_eq!(loaded.0[1].kills, 3);
+        assert_eq!(loaded.0[1].defection_time, Some(30.0));
+        let high_scores = loaded
+            .high_scores()
+            .into_iter()
+            .map(|run| run.score.total())
+            .collect::<Vec<_>>();
+        assert_eq!(high_scores, [20, 10]);
+    }
+}
@@ -7,17 +7,100 @@ mod game;
 mod sequence;
 mod util;
 
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
 use bevy::prelude::*;
 
-pub struct AppPlugin;
+use crate::common::config::ConfigLayer;
+use crate::sequence::replay::Replay;
+
+#[derive(Default)]
+pub struct AppPlugin {
+    /// Run without a window, renderer or audio device (for CI and tests)
+    pub headless: bool,
+    /// Record the inputs of each run to this file, with the run index appended to the file name
+    pub record: Option<PathBuf>,
+    /// Replay the inputs recorded in this file instead of live input
+    pub replay: Option<PathBuf>,
+}
+
+impl AppPlugin {
+    pub fn headless() -> Self {
+        Self {
+            headless: true,
+            ..default()
+        }
+    }
+}
+
+/// Validate a config file without starting the game
+pub fn check_config(path: impl AsRef<Path>) -> Result<(), String> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    ConfigLayer::parse(&bytes).map_err(|e| format!("{}:{e}", path.display()))?;
+
+    Ok(())
+}
 
 impl Plugin for AppPlugin {
     fn build(&self, app: &mut App) {
+        if let Some(path) = &self.replay {
+            app.insert_resource(Replay::playback(path));
+        } else if let Some(path) = &self.record {
+            app.insert_resource(Replay::record(path));
+        }
+
         app.add_plugins((
-            common::CommonPlugin,
+            common::CommonPlugin {
+                headless: self.headless,
+            },
             game::GamePlugin,
-            sequence::SequencePlugin,
+            sequence::SequencePlugin {
+                skip_menus: self.headless || self.replay.is_some(),
+            },
             util::UtilPlugin,
         ));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::Duration;
+
+    use bevy::prelude::*;
+
+    use super::*;
+    use crate::sequence::SequenceState;
+
+    #[test]
+    fn headless_app_reaches_game() {
+        let mut app = App::new();
+        app.add_plugins(AppPlugin::headless());
+        app.finish();
+        app.cleanup();
+
+        // Assets load in the background, so give Boot a few seconds to finish
+        let in_game =
+            |app: &App| *app.world.resource::<State<SequenceState>>().get() == SequenceState::Game;
+        for _ in 0..500 {
+            if in_game(&app) {
+                break;
+            }
+            app.update();
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(
+            in_game(&app),
+            "headless app never reached SequenceState::Game"
+        );
+
+        // Step gameplay for a moment to make sure the level spawns without panicking
+        for _ in 0..30 {
+            app.update();
+        }
+        assert!(in_game(&app));
+    }
+}
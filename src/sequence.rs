@@ -1,15 +1,34 @@
 mod boot;
 pub mod game;
+mod main_menu;
+pub mod pause;
+pub mod replay;
+pub mod settings;
+mod title_screen;
 
 use bevy::prelude::*;
 use strum::EnumIter;
 
-pub struct SequencePlugin;
+use crate::sequence::SequenceState::*;
+
+pub struct SequencePlugin {
+    /// Go straight from Boot to Game (for headless runs and replays)
+    pub skip_menus: bool,
+}
 
 impl Plugin for SequencePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<SequenceState>()
-            .add_plugins((boot::BootStatePlugin, game::GameStatePlugin));
+        app.init_state::<SequenceState>().add_plugins((
+            boot::BootStatePlugin {
+                next: if self.skip_menus { Game } else { TitleScreen },
+            },
+            title_screen::TitleScreenStatePlugin,
+            main_menu::MainMenuStatePlugin,
+            settings::SettingsStatePlugin,
+            game::GameStatePlugin,
+            pause::PauseStatePlugin,
+            replay::ReplayPlugin,
+        ));
     }
 }
 
@@ -17,7 +36,15 @@ impl Plugin for SequencePlugin {
 pub enum SequenceState {
     #[default]
     Boot,
+    TitleScreen,
+    MainMenu,
+    Settings,
     // TODO: Workaround for https://github.com/bevyengine/bevy/issues/9130
     RestartGame,
     Game,
 }
+
+/// A system that transitions to the given state (for button callbacks)
+fn enter_state(state: SequenceState) -> impl Fn(ResMut<NextState<SequenceState>>) {
+    move |mut next_state| next_state.set(state)
+}
@@ -1,15 +1,19 @@
+use bevy::asset::LoadState;
 use bevy::prelude::*;
 use iyes_progress::prelude::*;
 
 use crate::common::config::ConfigHandle;
 use crate::common::window::WindowRoot;
+use crate::sequence::SequenceState;
 use crate::sequence::SequenceState::*;
 
-pub struct BootStatePlugin;
+pub struct BootStatePlugin {
+    pub next: SequenceState,
+}
 
 impl Plugin for BootStatePlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(ProgressPlugin::new(Boot).continue_to(Game))
+        app.add_plugins(ProgressPlugin::new(Boot).continue_to(self.next))
             .add_systems(OnEnter(Boot), enter_boot)
             .add_systems(OnExit(Boot), exit_boot);
 
@@ -36,6 +40,12 @@ fn exit_boot(window_root: Res<WindowRoot>, mut window_query: Query<&mut Window>)
     window.visible = true;
 }
 
+// A broken config falls back to the built-in defaults instead of stalling here
 fn wait_for_config(ass: Res<AssetServer>, config_handle: Res<ConfigHandle>) -> Progress {
-    ass.is_loaded_with_dependencies(&config_handle.0).into()
+    (ass.is_loaded_with_dependencies(&config_handle.default)
+        || matches!(
+            ass.get_load_state(&config_handle.default),
+            Some(LoadState::Failed),
+        ))
+    .into()
 }
@@ -5,10 +5,16 @@ use bevy_kira_audio::prelude::*;
 use bevy_rapier2d::pipeline::CollisionEvent;
 use leafwing_input_manager::common_conditions::action_just_pressed;
 use leafwing_input_manager::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
 
+use crate::common::audio::UiChannel;
 use crate::common::camera::CameraRoot;
+use crate::common::rng::GameRng;
 use crate::common::UpdateSet;
+use crate::game::actor::enemy::AiStateEvent;
 use crate::game::actor::enemy::AlertEvent;
+use crate::game::actor::enemy::ShoutEvent;
 use crate::game::actor::player::Playthrough;
 use crate::game::alarm::Alarm;
 use crate::game::alarm::AlarmAssets;
@@ -17,9 +23,17 @@ use crate::game::combat::DeathEvent;
 use crate::game::combat::HitEvent;
 use crate::game::cutscene::CutsceneAssets;
 use crate::game::cutscene::CutsceneTemplate;
+use crate::game::interact::InteractEvent;
+use crate::game::interact::InteractPromptTemplate;
+use crate::game::inventory::InventoryHudTemplate;
 use crate::game::level::victory::Victory;
 use crate::game::level::LevelAssets;
 use crate::game::level::LevelTemplate;
+use crate::game::noise::NoiseEvent;
+use crate::game::score::HighScoreTable;
+use crate::game::score::HighScoreTableTemplate;
+use crate::game::score::RunHistory;
+use crate::sequence::replay::Replay;
 use crate::sequence::SequenceState;
 use crate::sequence::SequenceState::*;
 use crate::util::ui::UiRoot;
@@ -34,23 +48,22 @@ impl Plugin for GameStatePlugin {
         app.register_type::<GameRoot>().init_resource::<GameRoot>();
 
         app.add_systems(OnEnter(Game), enter_game)
-            .add_systems(OnExit(Game), exit_game)
+            .add_systems(OnExit(Game), (exit_game, clear_game_events))
             .add_systems(OnEnter(RestartGame), |mut state: ResMut<NextState<_>>| {
                 state.set(Game);
             });
 
+        // The InputMap is provided by KeybindingsPlugin
         app.init_resource::<ActionState<GameAction>>()
-            .insert_resource(
-                InputMap::default()
-                    .insert(GameAction::Restart, KeyCode::KeyR)
-                    .build(),
-            )
             .add_plugins(InputManagerPlugin::<GameAction>::default())
             .add_systems(
                 Update,
-                restart
+                (
+                    restart.run_if(action_just_pressed(GameAction::Restart)),
+                    toggle_high_scores.run_if(action_just_pressed(GameAction::HighScores)),
+                )
                     .in_set(UpdateSet::HandleActions)
-                    .run_if(in_state(Game).and_then(action_just_pressed(GameAction::Restart))),
+                    .run_if(in_state(Game)),
             );
     }
 }
@@ -85,18 +98,27 @@ fn enter_game(
     level_assets: Res<LevelAssets>,
     game_root: Res<GameRoot>,
     ui_root: Res<UiRoot>,
+    replay: Res<Replay>,
+    time: Res<Time<Fixed>>,
+    mut playthrough: ResMut<Playthrough>,
     mut seen_cutscene: Local<bool>,
 ) {
+    playthrough.run_start_time = time.elapsed_seconds();
+
     // Spawn level
     let level = LevelTemplate.spawn(&mut commands, &level_assets);
     commands.entity(level).set_parent(game_root.game);
+    let interact_prompt = InteractPromptTemplate.spawn(&mut commands);
+    commands.entity(interact_prompt).set_parent(game_root.game);
 
     // Spawn HUD
     let alarm_meter = AlarmMeterTemplate.spawn(&mut commands, &alarm_assets);
     commands.entity(alarm_meter).set_parent(ui_root.body);
+    let inventory_hud = InventoryHudTemplate.spawn(&mut commands);
+    commands.entity(inventory_hud).set_parent(ui_root.body);
 
-    // Spawn cutscene only on the first run
-    if !*seen_cutscene {
+    // Spawn cutscene only on the first run (advancing it is not part of a replay)
+    if !*seen_cutscene && replay.is_off() {
         *seen_cutscene = true;
 
         let cutscene = CutsceneTemplate.spawn(&mut commands, &cutscene_assets);
@@ -109,28 +131,20 @@ fn exit_game(
     game_root: Res<GameRoot>,
     ui_root: Res<UiRoot>,
     camera_root: Res<CameraRoot>,
-    mut collision_events: ResMut<Events<CollisionEvent>>,
-    mut hit_events: ResMut<Events<HitEvent>>,
-    mut death_events: ResMut<Events<DeathEvent>>,
-    mut detect_events: ResMut<Events<AlertEvent>>,
     mut level_selection: ResMut<LevelSelection>,
     mut playthrough: ResMut<Playthrough>,
     mut victory: ResMut<Victory>,
     mut alarm: ResMut<Alarm>,
+    mut rng: ResMut<GameRng>,
     mut camera_query: Query<&mut Transform>,
 ) {
     // Reset resources
     *level_selection = default();
+    *rng = default();
     *playthrough = default();
     *victory = default();
     *alarm = default();
 
-    // Clear events
-    collision_events.clear();
-    hit_events.clear();
-    death_events.clear();
-    detect_events.clear();
-
     // Despawn entities
     commands.entity(ui_root.body).despawn_descendants();
     commands.entity(game_root.game).despawn_descendants();
@@ -141,18 +155,61 @@ fn exit_game(
     };
 }
 
-#[derive(Actionlike, Reflect, Clone, Hash, PartialEq, Eq)]
+fn clear_game_events(
+    mut collision_events: ResMut<Events<CollisionEvent>>,
+    mut hit_events: ResMut<Events<HitEvent>>,
+    mut death_events: ResMut<Events<DeathEvent>>,
+    mut detect_events: ResMut<Events<AlertEvent>>,
+    mut state_events: ResMut<Events<AiStateEvent>>,
+    mut shout_events: ResMut<Events<ShoutEvent>>,
+    mut noise_events: ResMut<Events<NoiseEvent>>,
+    mut interact_events: ResMut<Events<InteractEvent>>,
+) {
+    collision_events.clear();
+    hit_events.clear();
+    death_events.clear();
+    detect_events.clear();
+    state_events.clear();
+    shout_events.clear();
+    noise_events.clear();
+    interact_events.clear();
+}
+
+#[derive(Actionlike, Reflect, Serialize, Deserialize, Clone, Hash, PartialEq, Eq, Debug)]
 pub enum GameAction {
     Restart,
+    HighScores,
+    Pause,
 }
 
 fn restart(
     mut state: ResMut<NextState<SequenceState>>,
     game_assets: Res<GameAssets>,
-    audio: Res<Audio>,
+    audio: Res<AudioChannel<UiChannel>>,
 ) {
     state.set(RestartGame);
 
     // Play restart sound
     audio.play(game_assets.sfx_restart.clone());
 }
+
+// The high score table is only reachable from the victory message
+fn toggle_high_scores(
+    mut commands: Commands,
+    ui_root: Res<UiRoot>,
+    victory: Res<Victory>,
+    history: Res<RunHistory>,
+    table_query: Query<Entity, With<HighScoreTable>>,
+) {
+    if !victory.0 {
+        return;
+    }
+
+    if let Ok(table) = table_query.get_single() {
+        commands.entity(table).despawn_recursive();
+        return;
+    }
+
+    let table = HighScoreTableTemplate { history: &history }.spawn(&mut commands);
+    commands.entity(table).set_parent(ui_root.body);
+}
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::game::score::HighScoreTable;
+use crate::game::score::HighScoreTableTemplate;
+use crate::game::score::RunHistory;
+use crate::sequence::enter_state;
+use crate::sequence::SequenceState::*;
+use crate::util::ui::menu::ButtonTemplate;
+use crate::util::ui::menu::MenuTemplate;
+use crate::util::ui::UiRoot;
+
+pub struct MainMenuStatePlugin;
+
+impl Plugin for MainMenuStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MainMenu), enter_main_menu)
+            .add_systems(OnExit(MainMenu), exit_main_menu);
+    }
+}
+
+fn enter_main_menu(mut commands: Commands, ui_root: Res<UiRoot>) {
+    let main_menu = MainMenuTemplate.spawn(&mut commands);
+    commands.entity(main_menu).set_parent(ui_root.body);
+}
+
+fn exit_main_menu(mut commands: Commands, ui_root: Res<UiRoot>) {
+    commands.entity(ui_root.body).despawn_descendants();
+}
+
+struct MainMenuTemplate;
+
+impl MainMenuTemplate {
+    fn spawn(self, commands: &mut Commands) -> Entity {
+        // Children
+        let play = ButtonTemplate::new("Play").spawn(commands);
+        commands
+            .entity(play)
+            .insert(On::<Pointer<Click>>::run(enter_state(Game)));
+
+        let settings = ButtonTemplate::new("Settings").spawn(commands);
+        commands
+            .entity(settings)
+            .insert(On::<Pointer<Click>>::run(enter_state(Settings)));
+
+        let high_scores = ButtonTemplate::new("High Scores").spawn(commands);
+        commands
+            .entity(high_scores)
+            .insert(On::<Pointer<Click>>::run(show_high_scores));
+
+        // Parent
+        let menu = MenuTemplate {
+            title: "Sai Defects".to_string(),
+        }
+        .spawn(commands);
+        commands
+            .entity(menu)
+            .push_children(&[play, settings, high_scores]);
+
+        // There's nothing to quit to on web
+        #[cfg(not(feature = "web"))]
+        {
+            let quit = ButtonTemplate::new("Quit").spawn(commands);
+            commands
+                .entity(quit)
+                .insert(On::<Pointer<Click>>::run(
+                    |mut app_exit: EventWriter<bevy::app::AppExit>| {
+                        app_exit.send(bevy::app::AppExit);
+                    },
+                ))
+                .set_parent(menu);
+        }
+
+        menu
+    }
+}
+
+fn show_high_scores(
+    mut commands: Commands,
+    ui_root: Res<UiRoot>,
+    history: Res<RunHistory>,
+    table_query: Query<(), With<HighScoreTable>>,
+) {
+    if !table_query.is_empty() {
+        return;
+    }
+
+    let table = HighScoreTableTemplate { history: &history }.spawn(&mut commands);
+    commands
+        .entity(table)
+        .insert(On::<Pointer<Click>>::run(hide_high_scores))
+        .set_parent(ui_root.body);
+}
+
+fn hide_high_scores(mut commands: Commands, table_query: Query<Entity, With<HighScoreTable>>) {
+    for table in &table_query {
+        commands.entity(table).despawn_recursive();
+    }
+}
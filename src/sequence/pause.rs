@@ -0,0 +1,213 @@
+use bevy::prelude::*;
+use bevy::ui::Val::*;
+use bevy_kira_audio::prelude::*;
+use bevy_mod_picking::prelude::*;
+use bevy_rapier2d::plugin::RapierConfiguration;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+
+use crate::common::music::Music;
+use crate::common::theme::ThemeBackgroundColor;
+use crate::common::theme::ThemeColor;
+use crate::common::UpdateSet;
+use crate::sequence::enter_state;
+use crate::sequence::game::GameAction;
+use crate::sequence::settings::is_rebinding;
+use crate::sequence::settings::SettingsMenuTemplate;
+use crate::sequence::SequenceState::*;
+use crate::util::ui::menu::ButtonTemplate;
+use crate::util::ui::menu::MenuTemplate;
+use crate::util::ui::UiRoot;
+
+pub struct PauseStatePlugin;
+
+impl Plugin for PauseStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<PauseState>()
+            .add_systems(OnEnter(PauseState::Paused), enter_paused)
+            .add_systems(OnExit(PauseState::Paused), exit_paused)
+            .add_systems(OnExit(Game), |mut pause: ResMut<NextState<_>>| {
+                pause.set(PauseState::Running);
+            });
+
+        // Gameplay in FixedUpdate is frozen by pausing virtual time, so only Update needs this
+        app.configure_sets(
+            Update,
+            (
+                UpdateSet::HandleActions,
+                UpdateSet::Start,
+                UpdateSet::Update,
+                UpdateSet::RecordIntents,
+                UpdateSet::BlockIntents,
+                UpdateSet::ApplyIntents,
+                UpdateSet::HandleEvents,
+            )
+                .run_if(in_state(PauseState::Running)),
+        );
+
+        app.register_type::<PauseMenu>().add_systems(
+            Update,
+            // Check for a rebinding before the rebind buttons stop listening
+            toggle_pause
+                .run_if(
+                    in_state(Game)
+                        .and_then(action_just_pressed(GameAction::Pause))
+                        .and_then(not(is_rebinding)),
+                )
+                .before(UpdateSet::UpdateUi),
+        );
+    }
+}
+
+/// (Game only) Whether gameplay is paused
+#[derive(States, Reflect, Default, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+fn toggle_pause(pause: Res<State<PauseState>>, mut next_pause: ResMut<NextState<PauseState>>) {
+    next_pause.set(match pause.get() {
+        PauseState::Running => PauseState::Paused,
+        PauseState::Paused => PauseState::Running,
+    });
+}
+
+fn enter_paused(
+    mut commands: Commands,
+    ui_root: Res<UiRoot>,
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    music: Res<Music>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    // Freeze gameplay (this includes the fixed clock used for Playthrough and Lifetime)
+    time.pause();
+    rapier_config.physics_pipeline_active = false;
+    if let Some(instance) = music
+        .track
+        .as_ref()
+        .and_then(|track| audio_instances.get_mut(track))
+    {
+        instance.pause(AudioTween::default());
+    }
+
+    let pause_menu = PauseMenuTemplate.spawn(&mut commands);
+    commands.entity(pause_menu).set_parent(ui_root.body);
+}
+
+fn exit_paused(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    music: Res<Music>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    pause_menu_query: Query<Entity, With<PauseMenu>>,
+) {
+    time.unpause();
+    rapier_config.physics_pipeline_active = true;
+    if let Some(instance) = music
+        .track
+        .as_ref()
+        .and_then(|track| audio_instances.get_mut(track))
+    {
+        instance.resume(AudioTween::default());
+    }
+
+    for entity in &pause_menu_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct PauseMenu;
+
+struct PauseMenuTemplate;
+
+impl PauseMenuTemplate {
+    fn spawn(self, commands: &mut Commands) -> Entity {
+        let pause_menu = commands
+            .spawn((
+                Name::new("PauseMenu"),
+                NodeBundle {
+                    style: Style {
+                        width: Percent(100.0),
+                        height: Percent(100.0),
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    ..default()
+                },
+                ThemeBackgroundColor(ThemeColor::Popup),
+                PauseMenu,
+            ))
+            .id();
+
+        let menu = spawn_main_page(commands);
+        commands.entity(menu).set_parent(pause_menu);
+
+        pause_menu
+    }
+}
+
+fn spawn_main_page(commands: &mut Commands) -> Entity {
+    // Children
+    let resume = ButtonTemplate::new("Resume").spawn(commands);
+    commands.entity(resume).insert(On::<Pointer<Click>>::run(
+        |mut pause: ResMut<NextState<PauseState>>| {
+            pause.set(PauseState::Running);
+        },
+    ));
+
+    let restart = ButtonTemplate::new("Restart").spawn(commands);
+    commands
+        .entity(restart)
+        .insert(On::<Pointer<Click>>::run(enter_state(RestartGame)));
+
+    let settings = ButtonTemplate::new("Settings").spawn(commands);
+    commands
+        .entity(settings)
+        .insert(On::<Pointer<Click>>::run(show_settings_page));
+
+    let quit = ButtonTemplate::new("Quit to Menu").spawn(commands);
+    commands
+        .entity(quit)
+        .insert(On::<Pointer<Click>>::run(enter_state(MainMenu)));
+
+    // Parent
+    let menu = MenuTemplate {
+        title: "Paused".to_string(),
+    }
+    .spawn(commands);
+    commands
+        .entity(menu)
+        .push_children(&[resume, restart, settings, quit]);
+
+    menu
+}
+
+fn show_settings_page(mut commands: Commands, pause_menu_query: Query<Entity, With<PauseMenu>>) {
+    let Ok(pause_menu) = pause_menu_query.get_single() else {
+        return;
+    };
+    commands.entity(pause_menu).despawn_descendants();
+
+    let menu = SettingsMenuTemplate.spawn(&mut commands);
+    commands.entity(menu).set_parent(pause_menu);
+
+    let back = ButtonTemplate::new("Back").spawn(&mut commands);
+    commands
+        .entity(back)
+        .insert(On::<Pointer<Click>>::run(show_main_page))
+        .set_parent(menu);
+}
+
+fn show_main_page(mut commands: Commands, pause_menu_query: Query<Entity, With<PauseMenu>>) {
+    let Ok(pause_menu) = pause_menu_query.get_single() else {
+        return;
+    };
+    commands.entity(pause_menu).despawn_descendants();
+
+    let menu = spawn_main_page(&mut commands);
+    commands.entity(menu).set_parent(pause_menu);
+}
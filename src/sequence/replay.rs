@@ -0,0 +1,331 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use bevy::app::AppExit;
+use bevy::ecs::system::SystemParam;
+use bevy::math::vec2;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::common::rng::GameRng;
+use crate::common::UpdateSet;
+use crate::game::actor::health::Health;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::actor::player::PlayerAction;
+use crate::game::actor::player::PlayerControl;
+use crate::game::actor::player::Playthrough;
+use crate::game::alarm::Alarm;
+use crate::game::level::victory::Victory;
+use crate::sequence::game::GameAction;
+use crate::sequence::SequenceState;
+use crate::sequence::SequenceState::*;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Replay>()
+            .add_systems(OnEnter(Game), start_replay)
+            .add_systems(OnExit(Game), stop_replay)
+            .add_systems(Last, save_on_app_exit.run_if(on_event::<AppExit>()))
+            .add_systems(
+                FixedUpdate,
+                (
+                    play_back_inputs.in_set(UpdateSet::HandleActions),
+                    record_inputs.in_set(UpdateSet::HandleActions),
+                    record_outcome.in_set(UpdateSet::End),
+                ),
+            );
+    }
+}
+
+/// The inputs of a single run, one entry per fixed timestep
+#[derive(Serialize, Deserialize, Default)]
+pub struct Recording {
+    pub seed: u64,
+    pub ticks: Vec<RecordedTick>,
+    pub outcome: Outcome,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+pub struct RecordedTick {
+    pub movement: Option<[f32; 2]>,
+    pub aim: Option<[f32; 2]>,
+    pub attack: bool,
+    pub throw: bool,
+    pub dodge: bool,
+    pub interact: bool,
+    pub drink: bool,
+    pub restart: bool,
+}
+
+/// The result of a run, used to detect when a replay diverges from its recording
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+pub struct Outcome {
+    pub defected: bool,
+    pub victory: bool,
+    pub death: bool,
+    pub alarm: f32,
+    pub health: f32,
+}
+
+#[derive(Resource, Default)]
+pub enum Replay {
+    #[default]
+    Off,
+    Record {
+        path: PathBuf,
+        /// The number of runs recorded so far, so that restarting doesn't overwrite the last run
+        run: usize,
+        recording: Recording,
+    },
+    Playback {
+        recording: Recording,
+        tick: usize,
+        /// The outcome of the replayed run so far
+        outcome: Outcome,
+    },
+}
+
+impl Replay {
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self::Record {
+            path: path.into(),
+            run: 0,
+            recording: default(),
+        }
+    }
+
+    pub fn playback(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let recording = match fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| ron::from_str(&text).map_err(|e| e.to_string()))
+        {
+            Ok(recording) => recording,
+            Err(e) => {
+                error!("Failed to load replay from {}: {e}", path.display());
+                return Self::Off;
+            },
+        };
+
+        Self::Playback {
+            recording,
+            tick: 0,
+            outcome: default(),
+        }
+    }
+
+    pub fn is_off(&self) -> bool {
+        matches!(self, Self::Off)
+    }
+}
+
+fn start_replay(mut replay: ResMut<Replay>, mut rng: ResMut<GameRng>) {
+    match replay.as_mut() {
+        Replay::Off => {},
+        Replay::Record { run, recording, .. } => {
+            *run += 1;
+            *recording = Recording {
+                seed: rng.seed(),
+                ..default()
+            };
+        },
+        Replay::Playback {
+            recording,
+            tick,
+            outcome,
+        } => {
+            *tick = 0;
+            *outcome = default();
+            *rng = GameRng::new(recording.seed);
+        },
+    }
+}
+
+fn stop_replay(mut replay: ResMut<Replay>) {
+    match replay.as_ref() {
+        Replay::Off => {},
+        Replay::Record {
+            path,
+            run,
+            recording,
+        } => save_recording(&run_path(path, *run), recording),
+        Replay::Playback {
+            recording, outcome, ..
+        } => {
+            // The run ended before the recorded inputs ran out
+            report_outcome(&recording.outcome, outcome);
+            *replay = Replay::Off;
+        },
+    }
+}
+
+// OnExit(Game) does not run when the window is closed mid-run
+fn save_on_app_exit(replay: Res<Replay>, state: Res<State<SequenceState>>) {
+    if let Replay::Record {
+        path,
+        run,
+        recording,
+    } = replay.as_ref()
+    {
+        if *state.get() == Game {
+            save_recording(&run_path(path, *run), recording);
+        }
+    }
+}
+
+/// Append the run index to the file name (e.g. `run.ron` becomes `run.2.ron`)
+fn run_path(path: &Path, run: usize) -> PathBuf {
+    let mut name = path.file_stem().unwrap_or_default().to_os_string();
+    name.push(format!(".{run}"));
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    path.with_file_name(name)
+}
+
+fn save_recording(path: &Path, recording: &Recording) {
+    let text = match ron::ser::to_string_pretty(recording, default()) {
+        Ok(text) => text,
+        Err(e) => {
+            error!("Failed to serialize replay: {e}");
+            return;
+        },
+    };
+    if let Err(e) = fs::write(path, text) {
+        error!("Failed to save replay to {}: {e}", path.display());
+        return;
+    }
+    info!("Saved replay to {}", path.display());
+}
+
+fn report_outcome(expected: &Outcome, actual: &Outcome) {
+    if expected == actual {
+        info!("Replay finished with the recorded outcome: {actual:?}");
+    } else {
+        warn!("Replay diverged from the recording: expected {expected:?}, got {actual:?}");
+    }
+}
+
+#[derive(SystemParam)]
+struct OutcomeParam<'w, 's> {
+    playthrough: Res<'w, Playthrough>,
+    victory: Res<'w, Victory>,
+    alarm: Res<'w, Alarm>,
+    player_query: Query<'w, 's, (&'static Health, Has<ActorIntent>), With<PlayerControl>>,
+}
+
+impl OutcomeParam<'_, '_> {
+    fn get(&self) -> Outcome {
+        let (health, alive) = self
+            .player_query
+            .get_single()
+            .map(|(health, alive)| (health.current, alive))
+            .unwrap_or((0.0, false));
+
+        Outcome {
+            defected: self.playthrough.defected,
+            victory: self.victory.0,
+            death: !alive,
+            alarm: self.alarm.0,
+            health,
+        }
+    }
+}
+
+fn record_inputs(
+    mut replay: ResMut<Replay>,
+    player_query: Query<&ActionState<PlayerAction>, With<PlayerControl>>,
+    game_action: Res<ActionState<GameAction>>,
+) {
+    let Replay::Record { recording, .. } = replay.as_mut() else {
+        return;
+    };
+    // Only count ticks once the level has spawned, since loading time varies between runs
+    let Ok(action) = player_query.get_single() else {
+        return;
+    };
+
+    let axis_pair = |action_type: &PlayerAction| {
+        action
+            .clamped_axis_pair(action_type)
+            .map(|axis_pair| axis_pair.xy().to_array())
+    };
+    recording.ticks.push(RecordedTick {
+        movement: action
+            .pressed(&PlayerAction::Move)
+            .then(|| axis_pair(&PlayerAction::Move))
+            .flatten(),
+        aim: axis_pair(&PlayerAction::Aim),
+        attack: action.pressed(&PlayerAction::Attack),
+        throw: action.pressed(&PlayerAction::Throw),
+        dodge: action.pressed(&PlayerAction::Dodge),
+        interact: action.pressed(&PlayerAction::Interact),
+        drink: action.pressed(&PlayerAction::Drink),
+        restart: game_action.pressed(&GameAction::Restart),
+    });
+}
+
+fn record_outcome(mut replay: ResMut<Replay>, outcome: OutcomeParam) {
+    match replay.as_mut() {
+        Replay::Off => {},
+        Replay::Record { recording, .. } => recording.outcome = outcome.get(),
+        Replay::Playback { outcome: old, .. } => *old = outcome.get(),
+    }
+}
+
+fn play_back_inputs(
+    mut replay: ResMut<Replay>,
+    mut player_query: Query<&mut ActionState<PlayerAction>, With<PlayerControl>>,
+    mut game_action: ResMut<ActionState<GameAction>>,
+) {
+    let Replay::Playback {
+        recording,
+        tick,
+        outcome,
+    } = replay.as_mut()
+    else {
+        return;
+    };
+    let Ok(mut action) = player_query.get_single_mut() else {
+        return;
+    };
+
+    // Hand control back to the player once the recording runs out
+    let Some(&recorded) = recording.ticks.get(*tick) else {
+        report_outcome(&recording.outcome, outcome);
+        *replay = Replay::Off;
+        return;
+    };
+    *tick += 1;
+
+    let mut set_action = |action_type: PlayerAction, pressed: bool, axis_pair: Option<[f32; 2]>| {
+        if pressed {
+            action.press(&action_type);
+        } else {
+            action.release(&action_type);
+        }
+        action.action_data_mut_or_default(&action_type).axis_pair =
+            axis_pair.map(|[x, y]| DualAxisData::from_xy(vec2(x, y)));
+    };
+    set_action(
+        PlayerAction::Move,
+        recorded.movement.is_some(),
+        recorded.movement,
+    );
+    set_action(PlayerAction::Aim, recorded.aim.is_some(), recorded.aim);
+    set_action(PlayerAction::Attack, recorded.attack, None);
+    set_action(PlayerAction::Throw, recorded.throw, None);
+    set_action(PlayerAction::Dodge, recorded.dodge, None);
+    set_action(PlayerAction::Interact, recorded.interact, None);
+    set_action(PlayerAction::Drink, recorded.drink, None);
+
+    if recorded.restart {
+        game_action.press(&GameAction::Restart);
+    }
+}
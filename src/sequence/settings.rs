@@ -0,0 +1,255 @@
+use bevy::prelude::*;
+use bevy::window::WindowMode;
+use bevy_mod_picking::prelude::*;
+
+use crate::common::config::Config;
+use crate::common::config::UserConfig;
+use crate::common::keybindings::Binding;
+use crate::common::keybindings::BoundAction;
+use crate::common::keybindings::Keybindings;
+use crate::common::storage::ConfigStorage;
+use crate::common::window::WindowRoot;
+use crate::common::UpdateSet;
+use crate::game::actor::player::PlayerAction;
+use crate::game::cutscene::CutsceneAction;
+use crate::sequence::game::GameAction;
+use crate::sequence::SequenceState;
+use crate::sequence::SequenceState::*;
+use crate::util::ui::menu::ButtonTemplate;
+use crate::util::ui::menu::MenuTemplate;
+use crate::util::ui::UiRoot;
+
+pub struct SettingsStatePlugin;
+
+impl Plugin for SettingsStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(Settings), enter_settings)
+            .add_systems(OnExit(Settings), exit_settings);
+
+        app.register_type::<FullscreenButton>()
+            .add_systems(Update, update_fullscreen_button.in_set(UpdateSet::UpdateUi));
+
+        app.register_type::<RebindButton>().add_systems(
+            Update,
+            // Not in HandleActions, so that rebinding also works while paused
+            (capture_rebinding, update_rebind_buttons)
+                .chain()
+                .in_set(UpdateSet::UpdateUi),
+        );
+    }
+}
+
+fn enter_settings(mut commands: Commands, ui_root: Res<UiRoot>) {
+    let menu = SettingsMenuTemplate.spawn(&mut commands);
+    commands.entity(menu).set_parent(ui_root.body);
+
+    let back = ButtonTemplate::new("Back").spawn(&mut commands);
+    commands
+        .entity(back)
+        .insert(On::<Pointer<Click>>::run(
+            |mut state: ResMut<NextState<SequenceState>>| {
+                state.set(MainMenu);
+            },
+        ))
+        .set_parent(menu);
+}
+
+fn exit_settings(mut commands: Commands, ui_root: Res<UiRoot>) {
+    commands.entity(ui_root.body).despawn_descendants();
+}
+
+/// The settings menu without a back button, so it can be reused outside of the Settings state
+pub struct SettingsMenuTemplate;
+
+impl SettingsMenuTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        // Children
+        let fullscreen = ButtonTemplate::new("").spawn(commands);
+        commands.entity(fullscreen).insert((
+            FullscreenButton,
+            On::<Pointer<Click>>::run(toggle_fullscreen),
+        ));
+
+        let rebind_buttons = REBINDABLE_ACTIONS
+            .into_iter()
+            .map(|(label, action)| {
+                let button = ButtonTemplate::new("").spawn(commands);
+                commands.entity(button).insert((
+                    RebindButton {
+                        label: label.to_string(),
+                        action,
+                        listening: false,
+                    },
+                    On::<Pointer<Click>>::run(start_rebinding),
+                ));
+                button
+            })
+            .collect::<Vec<_>>();
+
+        // Parent
+        let menu = MenuTemplate {
+            title: "Settings".to_string(),
+        }
+        .spawn(commands);
+        commands
+            .entity(menu)
+            .add_child(fullscreen)
+            .push_children(&rebind_buttons);
+
+        menu
+    }
+}
+
+#[derive(Component, Reflect)]
+struct FullscreenButton;
+
+// Settings are saved to the user config layer, which is then reapplied by apply_config
+fn toggle_fullscreen(
+    config: Res<Config>,
+    mut user_config: ResMut<UserConfig>,
+    mut storage: ResMut<ConfigStorage>,
+) {
+    user_config.0.window.window_mode = Some(match config.window.window_mode {
+        WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+        _ => WindowMode::Windowed,
+    });
+    user_config.save(&mut storage);
+}
+
+fn update_fullscreen_button(
+    window_root: Res<WindowRoot>,
+    window_query: Query<&Window>,
+    button_query: Query<&Children, With<FullscreenButton>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let Ok(window) = window_query.get(window_root.primary) else {
+        return;
+    };
+    let value = if matches!(window.mode, WindowMode::Windowed) {
+        "Fullscreen: Off"
+    } else {
+        "Fullscreen: On"
+    };
+
+    for children in &button_query {
+        for &child in children {
+            let Ok(mut text) = text_query.get_mut(child) else {
+                continue;
+            };
+            if text.sections[0].value != value {
+                text.sections[0].value = value.to_string();
+            }
+        }
+    }
+}
+
+const REBINDABLE_ACTIONS: [(&str, BoundAction); 9] = [
+    ("Attack", BoundAction::Player(PlayerAction::Attack)),
+    ("Throw", BoundAction::Player(PlayerAction::Throw)),
+    ("Dodge", BoundAction::Player(PlayerAction::Dodge)),
+    ("Interact", BoundAction::Player(PlayerAction::Interact)),
+    ("Drink Potion", BoundAction::Player(PlayerAction::Drink)),
+    ("Pause", BoundAction::Game(GameAction::Pause)),
+    ("Restart", BoundAction::Game(GameAction::Restart)),
+    ("High Scores", BoundAction::Game(GameAction::HighScores)),
+    (
+        "Skip Cutscene",
+        BoundAction::Cutscene(CutsceneAction::Advance),
+    ),
+];
+
+/// Rebinds its action to the next input after being clicked
+#[derive(Component, Reflect)]
+pub struct RebindButton {
+    label: String,
+    action: BoundAction,
+    listening: bool,
+}
+
+fn start_rebinding(
+    click: Listener<Pointer<Click>>,
+    mut button_query: Query<(Entity, &mut RebindButton)>,
+) {
+    for (entity, mut button) in &mut button_query {
+        button.listening = entity == click.listener();
+    }
+}
+
+/// A run condition for input handling that should wait until the rebinding is captured
+pub fn is_rebinding(button_query: Query<&RebindButton>) -> bool {
+    button_query.iter().any(|button| button.listening)
+}
+
+fn capture_rebinding(
+    mut button_query: Query<&mut RebindButton>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepad: Res<ButtonInput<GamepadButton>>,
+    keybindings: Res<Keybindings>,
+    mut user_config: ResMut<UserConfig>,
+    mut storage: ResMut<ConfigStorage>,
+) {
+    let Some(mut button) = button_query.iter_mut().find(|button| button.listening) else {
+        return;
+    };
+    // Escape is reserved for cancelling
+    if keyboard.just_pressed(KeyCode::Escape) {
+        button.listening = false;
+        return;
+    }
+    let Some(binding) = keyboard
+        .get_just_pressed()
+        .next()
+        .map(|&key| Binding::Key(key))
+        .or_else(|| {
+            mouse
+                .get_just_pressed()
+                .next()
+                .map(|&button| Binding::Mouse(button))
+        })
+        .or_else(|| {
+            gamepad
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Gamepad(button.button_type))
+        })
+    else {
+        return;
+    };
+
+    button.listening = false;
+    keybindings.rebind(
+        button.action.clone(),
+        binding,
+        &mut user_config,
+        &mut storage,
+    );
+}
+
+fn update_rebind_buttons(
+    keybindings: Res<Keybindings>,
+    button_query: Query<(&RebindButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (button, children) in &button_query {
+        let value = if button.listening {
+            format!("{}: press any input (Escape to cancel)...", button.label)
+        } else {
+            let bindings = keybindings
+                .get(&button.action)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            format!("{}: {}", button.label, bindings.join(", "))
+        };
+
+        for &child in children {
+            let Ok(mut text) = text_query.get_mut(child) else {
+                continue;
+            };
+            if text.sections[0].value != value {
+                text.sections[0].value.clone_from(&value);
+            }
+        }
+    }
+}
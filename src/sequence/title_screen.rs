@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+use bevy::ui::Val::*;
+use bevy_mod_picking::prelude::*;
+use leafwing_input_manager::common_conditions::action_just_pressed;
+use leafwing_input_manager::prelude::*;
+
+use crate::common::theme::ThemeColor;
+use crate::common::theme::ThemeTextColors;
+use crate::common::UpdateSet;
+use crate::sequence::SequenceState;
+use crate::sequence::SequenceState::*;
+use crate::util::ui::font::FontSize;
+use crate::util::ui::font::PIXEL_FONT_HANDLE;
+use crate::util::ui::UiRoot;
+
+pub struct TitleScreenStatePlugin;
+
+impl Plugin for TitleScreenStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(TitleScreen), enter_title_screen)
+            .add_systems(OnExit(TitleScreen), exit_title_screen);
+
+        app.init_resource::<ActionState<TitleScreenAction>>()
+            .insert_resource(
+                InputMap::default()
+                    .insert(TitleScreenAction::Start, KeyCode::Space)
+                    .insert(TitleScreenAction::Start, KeyCode::Enter)
+                    .insert(TitleScreenAction::Start, MouseButton::Left)
+                    .build(),
+            )
+            .add_plugins(InputManagerPlugin::<TitleScreenAction>::default())
+            .add_systems(
+                Update,
+                start.in_set(UpdateSet::HandleActions).run_if(
+                    in_state(TitleScreen).and_then(action_just_pressed(TitleScreenAction::Start)),
+                ),
+            );
+    }
+}
+
+fn enter_title_screen(mut commands: Commands, ui_root: Res<UiRoot>) {
+    let title_screen = TitleScreenTemplate.spawn(&mut commands);
+    commands.entity(title_screen).set_parent(ui_root.body);
+}
+
+fn exit_title_screen(mut commands: Commands, ui_root: Res<UiRoot>) {
+    commands.entity(ui_root.body).despawn_descendants();
+}
+
+#[derive(Actionlike, Reflect, Clone, Hash, PartialEq, Eq)]
+pub enum TitleScreenAction {
+    Start,
+}
+
+fn start(mut state: ResMut<NextState<SequenceState>>) {
+    state.set(MainMenu);
+}
+
+struct TitleScreenTemplate;
+
+impl TitleScreenTemplate {
+    fn spawn(self, commands: &mut Commands) -> Entity {
+        // Children
+        let title = commands
+            .spawn((
+                Name::new("Title"),
+                TextBundle::from_section(
+                    "Sai Defects",
+                    TextStyle {
+                        font: PIXEL_FONT_HANDLE,
+                        ..default()
+                    },
+                ),
+                FontSize::new(Vw(8.0)).with_step(8.0),
+                ThemeTextColors(vec![ThemeColor::BodyText]),
+                Pickable::IGNORE,
+            ))
+            .id();
+        let prompt = commands
+            .spawn((
+                Name::new("Prompt"),
+                TextBundle {
+                    style: Style {
+                        margin: UiRect::top(Vw(6.0)),
+                        ..default()
+                    },
+                    ..TextBundle::from_section(
+                        "(press Space to start)",
+                        TextStyle {
+                            font: PIXEL_FONT_HANDLE,
+                            ..default()
+                        },
+                    )
+                },
+                FontSize::new(Vw(2.0)).with_step(8.0),
+                ThemeTextColors(vec![ThemeColor::BodyText]),
+                Pickable::IGNORE,
+            ))
+            .id();
+
+        // Parent
+        commands
+            .spawn((
+                Name::new("TitleScreen"),
+                NodeBundle {
+                    style: Style {
+                        width: Percent(100.0),
+                        height: Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ))
+            .push_children(&[title, prompt])
+            .id()
+    }
+}
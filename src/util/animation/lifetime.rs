@@ -8,7 +8,7 @@ pub struct LifetimePlugin;
 impl Plugin for LifetimePlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Lifetime>()
-            .add_systems(Update, apply_lifetime.in_set(UpdateSet::Start));
+            .add_systems(FixedUpdate, apply_lifetime.in_set(UpdateSet::Start));
     }
 }
 
@@ -1,3 +1,4 @@
+use bevy::ecs::schedule::ScheduleLabel;
 use bevy::prelude::*;
 use bevy::utils::HashSet;
 
@@ -8,9 +9,11 @@ pub struct DespawnPlugin;
 impl Plugin for DespawnPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<DespawnSet>()
-            .init_resource::<DespawnSet>()
-            .add_systems(
-                Update,
+            .init_resource::<DespawnSet>();
+
+        for schedule in [Update.intern(), FixedUpdate.intern()] {
+            app.add_systems(
+                schedule,
                 (
                     // Flush queued commands first to prevent double despawn
                     apply_deferred.in_set(UpdateSet::QueueDespawn),
@@ -18,6 +21,7 @@ impl Plugin for DespawnPlugin {
                 )
                     .chain(),
             );
+        }
     }
 }
 
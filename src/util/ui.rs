@@ -2,6 +2,7 @@ pub mod backdrop;
 pub mod font;
 pub mod health_bar;
 pub mod interaction;
+pub mod menu;
 pub mod nametag;
 
 use bevy::prelude::*;
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use bevy::ui::Val::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::common::theme::ThemeBackgroundColor;
+use crate::common::theme::ThemeColor;
+use crate::common::theme::ThemeTextColors;
+use crate::util::ui::font::FontSize;
+use crate::util::ui::font::PIXEL_FONT_HANDLE;
+use crate::util::ui::interaction::InteractionPalette;
+
+/// A menu button; attach an `On::<Pointer<Click>>` to handle clicks
+pub struct ButtonTemplate {
+    pub text: String,
+}
+
+impl ButtonTemplate {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into() }
+    }
+
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        // Children
+        let text = commands
+            .spawn((
+                Name::new("ButtonText"),
+                TextBundle::from_section(
+                    self.text,
+                    TextStyle {
+                        font: PIXEL_FONT_HANDLE,
+                        ..default()
+                    },
+                ),
+                FontSize::new(Vw(2.0)).with_step(8.0),
+                ThemeTextColors(vec![ThemeColor::PrimaryText]),
+                Pickable::IGNORE,
+            ))
+            .id();
+
+        // Parent
+        commands
+            .spawn((
+                Name::new("Button"),
+                NodeBundle {
+                    style: Style {
+                        width: Vw(24.0),
+                        height: Vw(5.0),
+                        margin: UiRect::vertical(Vw(0.8)),
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    ..default()
+                },
+                Interaction::default(),
+                ThemeBackgroundColor(ThemeColor::Primary),
+                InteractionPalette {
+                    normal: ThemeColor::Primary,
+                    hovered: ThemeColor::PrimaryHovered,
+                    pressed: ThemeColor::PrimaryPressed,
+                    disabled: ThemeColor::PrimaryDisabled,
+                },
+            ))
+            .add_child(text)
+            .id()
+    }
+}
+
+/// A vertical stack of menu buttons, centered on the screen
+pub struct MenuTemplate {
+    pub title: String,
+}
+
+impl MenuTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        // Children
+        let title = commands
+            .spawn((
+                Name::new("MenuTitle"),
+                TextBundle {
+                    style: Style {
+                        margin: UiRect::bottom(Vw(3.0)),
+                        ..default()
+                    },
+                    ..TextBundle::from_section(
+                        self.title,
+                        TextStyle {
+                            font: PIXEL_FONT_HANDLE,
+                            ..default()
+                        },
+                    )
+                },
+                FontSize::new(Vw(4.0)).with_step(8.0),
+                ThemeTextColors(vec![ThemeColor::BodyText]),
+                Pickable::IGNORE,
+            ))
+            .id();
+
+        // Parent
+        commands
+            .spawn((
+                Name::new("Menu"),
+                NodeBundle {
+                    style: Style {
+                        width: Percent(100.0),
+                        height: Percent(100.0),
+                        position_type: PositionType::Absolute,
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ))
+            .add_child(title)
+            .id()
+    }
+}